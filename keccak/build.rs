@@ -0,0 +1,18 @@
+//! Detect whether the compiler supports AVX-512 intrinsics, which are stable since Rust 1.89.
+use std::{env, process::Command};
+
+fn main() {
+    println!("cargo:rerun-if-env-changed=RUSTC");
+
+    let rustc = env::var_os("RUSTC").unwrap_or_else(|| "rustc".into());
+    let minor = Command::new(rustc)
+        .arg("--version")
+        .output()
+        .ok()
+        .and_then(|out| String::from_utf8(out.stdout).ok())
+        .and_then(|version| version.split(['.', ' ']).nth(2)?.parse::<u32>().ok());
+
+    if minor.is_some_and(|minor| minor >= 89) {
+        println!("cargo:rustc-cfg=keccak_avx512");
+    }
+}
@@ -0,0 +1,561 @@
+//! Algebraic representations of Keccak-p permutations for cryptanalysis.
+//!
+//! [`anf`] computes the algebraic normal form of every output bit of Keccak-p as
+//! a polynomial over `GF(2)`, while [`cnf`] encodes the permutation as a CNF formula
+//! which can be passed to SAT solvers in the DIMACS format.
+//!
+//! Both functions work over lanes of type `L` implementing [`LaneSize`], i.e. they
+//! describe the same permutation as [`keccak_p::<L, ROUNDS>`][crate::generic::keccak_p].
+//! Bit `z` of the lane with index `i` has index `w * i + z` in the input and output slices,
+//! where `w` is the lane size. Every input bit is marked either as a constant or as
+//! a variable. Variables are numbered from zero in the order of the input bits, variable
+//! `k` of the ANF corresponds to the DIMACS variable `k + 1` of the CNF.
+//!
+//! The size of the ANF grows exponentially with the number of rounds and it is practical
+//! only for a few rounds or a small number of variables. The CNF is linear in size.
+use crate::consts::{PLEN, round_constant};
+use crate::generic::{LaneSize, keccak_f_round_count};
+use crate::steps::RHO_OFFSETS;
+use alloc::{collections::BTreeSet, vec::Vec};
+use core::{fmt, ops, ops::Range};
+
+/// Input bit of the permutation.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum InputBit {
+    /// The bit is a new variable.
+    Variable,
+    /// The bit has a fixed value.
+    Constant(bool),
+}
+
+/// Create input bits with constant values equal to the bits of `state`.
+///
+/// Individual bits can be replaced with [`InputBit::Variable`] afterwards.
+pub fn constant_input<L: LaneSize + Into<u64>>(state: &[L; PLEN]) -> Vec<InputBit> {
+    state
+        .iter()
+        .flat_map(|&lane| {
+            let lane = lane.into();
+            (0..L::BITS).map(move |z| InputBit::Constant((lane >> z) & 1 == 1))
+        })
+        .collect()
+}
+
+/// Builder of the algebraic representation of the permutation bits.
+trait Builder {
+    type Bit: Clone;
+
+    fn not(&mut self, a: &Self::Bit) -> Self::Bit;
+    fn xor(&mut self, a: &Self::Bit, b: &Self::Bit) -> Self::Bit;
+    /// Compute `a ^ (!b & c)`.
+    fn chi(&mut self, a: &Self::Bit, b: &Self::Bit, c: &Self::Bit) -> Self::Bit;
+}
+
+/// Apply the Keccak-p rounds with indices `rounds` to the state of bits with lanes of `w` bits.
+fn permute<B: Builder>(
+    builder: &mut B,
+    mut state: Vec<B::Bit>,
+    w: usize,
+    rounds: Range<i64>,
+) -> Vec<B::Bit> {
+    let idx = |x: usize, y: usize, z: usize| w * (x + 5 * y) + z;
+    for ir in rounds {
+        // Theta
+        let mut c = Vec::with_capacity(5 * w);
+        for x in 0..5 {
+            for z in 0..w {
+                let mut parity = state[idx(x, 0, z)].clone();
+                for y in 1..5 {
+                    parity = builder.xor(&parity, &state[idx(x, y, z)]);
+                }
+                c.push(parity);
+            }
+        }
+        for x in 0..5 {
+            for z in 0..w {
+                let d = builder.xor(
+                    &c[w * ((x + 4) % 5) + z],
+                    &c[w * ((x + 1) % 5) + (z + w - 1) % w],
+                );
+                for y in 0..5 {
+                    state[idx(x, y, z)] = builder.xor(&state[idx(x, y, z)], &d);
+                }
+            }
+        }
+
+        // Rho and pi
+        let src = state.clone();
+        for x in 0..5 {
+            for y in 0..5 {
+                let i = (x + 3 * y) % 5 + 5 * x;
+                let r = RHO_OFFSETS[i] as usize % w;
+                for z in 0..w {
+                    state[idx(x, y, z)] = src[w * i + (z + w - r) % w].clone();
+                }
+            }
+        }
+
+        // Chi
+        let src = state.clone();
+        for x in 0..5 {
+            for y in 0..5 {
+                for z in 0..w {
+                    state[idx(x, y, z)] = builder.chi(
+                        &src[idx(x, y, z)],
+                        &src[idx((x + 1) % 5, y, z)],
+                        &src[idx((x + 2) % 5, y, z)],
+                    );
+                }
+            }
+        }
+
+        // Iota
+        let rc = round_constant(ir);
+        for z in 0..w {
+            if (rc >> z) & 1 == 1 {
+                state[z] = builder.not(&state[z]);
+            }
+        }
+    }
+    state
+}
+
+/// Check length of `input` and return the lane size and the round indices.
+#[allow(clippy::cast_possible_wrap)]
+fn params<L: LaneSize, const ROUNDS: usize>(input: &[InputBit]) -> (usize, Range<i64>) {
+    let w = L::BITS as usize;
+    assert_eq!(input.len(), PLEN * w, "invalid number of input bits");
+    let last = keccak_f_round_count::<L>() as i64;
+    (w, last - ROUNDS as i64..last)
+}
+
+/// Polynomial over `GF(2)` in algebraic normal form.
+///
+/// The polynomial is a sum of monomials, every monomial is a product of distinct variables.
+/// The empty monomial is equal to 1.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Anf {
+    monomials: BTreeSet<Vec<u32>>,
+}
+
+impl Anf {
+    /// Create a constant polynomial.
+    #[must_use]
+    pub fn constant(value: bool) -> Self {
+        let mut res = Self::default();
+        if value {
+            res.monomials.insert(Vec::new());
+        }
+        res
+    }
+
+    /// Create a polynomial equal to the variable with index `var`.
+    #[must_use]
+    pub fn variable(var: u32) -> Self {
+        let mut res = Self::default();
+        res.monomials.insert([var].into());
+        res
+    }
+
+    /// Return `Some` with the value of the polynomial if it is constant.
+    #[must_use]
+    pub fn as_constant(&self) -> Option<bool> {
+        match self.monomials.first() {
+            None => Some(false),
+            Some(m) if m.is_empty() && self.monomials.len() == 1 => Some(true),
+            Some(_) => None,
+        }
+    }
+
+    /// Algebraic degree of the polynomial. The degree of zero is 0.
+    #[must_use]
+    pub fn degree(&self) -> usize {
+        self.monomials.iter().map(Vec::len).max().unwrap_or(0)
+    }
+
+    /// Iterate over the monomials as lists of variable indices in ascending order.
+    ///
+    /// The monomials are ordered lexicographically.
+    pub fn monomials(&self) -> impl Iterator<Item = &[u32]> {
+        self.monomials.iter().map(Vec::as_slice)
+    }
+
+    /// Evaluate the polynomial with variable `k` equal to `vars[k]`.
+    ///
+    /// # Panics
+    /// If `vars` is shorter than the biggest variable index used by the polynomial.
+    #[must_use]
+    pub fn evaluate(&self, vars: &[bool]) -> bool {
+        self.monomials
+            .iter()
+            .filter(|m| m.iter().all(|&v| vars[v as usize]))
+            .count()
+            % 2
+            == 1
+    }
+
+    fn toggle(&mut self, monomial: Vec<u32>) {
+        if !self.monomials.remove(&monomial) {
+            self.monomials.insert(monomial);
+        }
+    }
+}
+
+impl ops::BitXor for &Anf {
+    type Output = Anf;
+
+    fn bitxor(self, rhs: Self) -> Anf {
+        let monomials = self
+            .monomials
+            .symmetric_difference(&rhs.monomials)
+            .cloned()
+            .collect();
+        Anf { monomials }
+    }
+}
+
+impl ops::BitAnd for &Anf {
+    type Output = Anf;
+
+    fn bitand(self, rhs: Self) -> Anf {
+        let mut res = Anf::default();
+        for a in &self.monomials {
+            for b in &rhs.monomials {
+                let mut m: Vec<u32> = a.iter().chain(b).copied().collect();
+                m.sort_unstable();
+                m.dedup();
+                res.toggle(m);
+            }
+        }
+        res
+    }
+}
+
+impl ops::Not for &Anf {
+    type Output = Anf;
+
+    fn not(self) -> Anf {
+        let mut res = self.clone();
+        res.toggle(Vec::new());
+        res
+    }
+}
+
+impl fmt::Display for Anf {
+    /// Write the polynomial as e.g. `1 + x0 + x1*x3`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.monomials.is_empty() {
+            return f.write_str("0");
+        }
+        for (i, m) in self.monomials.iter().enumerate() {
+            if i != 0 {
+                f.write_str(" + ")?;
+            }
+            if m.is_empty() {
+                f.write_str("1")?;
+            }
+            for (j, var) in m.iter().enumerate() {
+                if j != 0 {
+                    f.write_str("*")?;
+                }
+                write!(f, "x{var}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+struct AnfBuilder;
+
+impl Builder for AnfBuilder {
+    type Bit = Anf;
+
+    fn not(&mut self, a: &Anf) -> Anf {
+        !a
+    }
+
+    fn xor(&mut self, a: &Anf, b: &Anf) -> Anf {
+        a ^ b
+    }
+
+    fn chi(&mut self, a: &Anf, b: &Anf, c: &Anf) -> Anf {
+        a ^ &(&!b & c)
+    }
+}
+
+/// Compute the algebraic normal form of the output bits of Keccak-p with `ROUNDS` rounds
+/// over lanes of type `L`.
+///
+/// # Panics
+/// If length of `input` is not equal to `25 * L::BITS`.
+#[must_use]
+pub fn anf<L: LaneSize, const ROUNDS: usize>(input: &[InputBit]) -> Vec<Anf> {
+    let (w, rounds) = params::<L, ROUNDS>(input);
+    let mut vars = 0;
+    let state = input
+        .iter()
+        .map(|bit| match *bit {
+            InputBit::Variable => {
+                vars += 1;
+                Anf::variable(vars - 1)
+            }
+            InputBit::Constant(value) => Anf::constant(value),
+        })
+        .collect();
+    permute(&mut AnfBuilder, state, w, rounds)
+}
+
+/// Bit of the CNF encoding.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum CnfBit {
+    /// The bit has a fixed value.
+    Constant(bool),
+    /// The bit is equal to the DIMACS literal, i.e. to the variable `l` for positive `l`
+    /// and to the negation of the variable `-l` for negative `l`.
+    Literal(i32),
+}
+
+impl CnfBit {
+    /// Value of the bit under `assignment`, where `assignment[k]` is the value
+    /// of the DIMACS variable `k + 1`.
+    ///
+    /// # Panics
+    /// If `assignment` does not contain the variable of the literal.
+    #[must_use]
+    pub fn value(self, assignment: &[bool]) -> bool {
+        match self {
+            CnfBit::Constant(value) => value,
+            CnfBit::Literal(lit) => literal_value(lit, assignment),
+        }
+    }
+}
+
+fn literal_value(lit: i32, assignment: &[bool]) -> bool {
+    assignment[(lit.unsigned_abs() - 1) as usize] ^ (lit < 0)
+}
+
+/// Definition of an auxiliary variable as a function of up to 3 literals.
+#[derive(Clone, Debug)]
+struct Gate {
+    inputs: Vec<i32>,
+    /// Bit `j` is the value of the variable when bit `k` of `j` is the value of `inputs[k]`.
+    table: u8,
+}
+
+/// CNF encoding of a Keccak-p permutation.
+///
+/// The DIMACS variables `1..=num_inputs` are the input variables, the remaining variables
+/// are auxiliary variables defined by the clauses as functions of the preceding ones.
+/// Every assignment of the input variables thus has exactly one extension satisfying
+/// the formula, which can be computed with [`Cnf::assignment`].
+#[derive(Clone, Debug)]
+pub struct Cnf {
+    width: usize,
+    rounds: usize,
+    num_inputs: usize,
+    gates: Vec<Gate>,
+    clauses: Vec<Vec<i32>>,
+    outputs: Vec<CnfBit>,
+}
+
+impl Cnf {
+    /// Number of the input variables.
+    #[must_use]
+    pub fn num_inputs(&self) -> usize {
+        self.num_inputs
+    }
+
+    /// Number of all variables.
+    #[must_use]
+    pub fn num_vars(&self) -> usize {
+        self.num_inputs + self.gates.len()
+    }
+
+    /// Clauses of the formula as lists of DIMACS literals.
+    #[must_use]
+    pub fn clauses(&self) -> &[Vec<i32>] {
+        &self.clauses
+    }
+
+    /// Output bits of the permutation.
+    #[must_use]
+    pub fn outputs(&self) -> &[CnfBit] {
+        &self.outputs
+    }
+
+    /// Compute the assignment of all variables satisfying the formula from the values
+    /// of the input variables.
+    ///
+    /// # Panics
+    /// If length of `inputs` is not equal to the number of the input variables.
+    #[must_use]
+    pub fn assignment(&self, inputs: &[bool]) -> Vec<bool> {
+        assert_eq!(inputs.len(), self.num_inputs, "invalid number of inputs");
+        let mut res = Vec::with_capacity(self.num_vars());
+        res.extend_from_slice(inputs);
+        for gate in &self.gates {
+            let j = gate.inputs.iter().enumerate().fold(0, |j, (k, &lit)| {
+                j | usize::from(literal_value(lit, &res)) << k
+            });
+            res.push((gate.table >> j) & 1 == 1);
+        }
+        res
+    }
+
+    /// Check whether `assignment` satisfies all clauses, where `assignment[k]` is the value
+    /// of the DIMACS variable `k + 1`.
+    ///
+    /// # Panics
+    /// If `assignment` is shorter than the number of variables.
+    #[must_use]
+    pub fn is_satisfied(&self, assignment: &[bool]) -> bool {
+        self.clauses
+            .iter()
+            .all(|clause| clause.iter().any(|&lit| literal_value(lit, assignment)))
+    }
+
+    /// Write the formula in the DIMACS format.
+    ///
+    /// The header is preceded by comments describing the permutation and the output bits.
+    ///
+    /// # Errors
+    /// If writing into `w` fails.
+    pub fn write_dimacs(&self, w: &mut impl fmt::Write) -> fmt::Result {
+        writeln!(w, "c Keccak-p[{}, nr={}]", self.width, self.rounds)?;
+        writeln!(w, "c input variables: 1..={}", self.num_inputs)?;
+        for (i, bit) in self.outputs.iter().enumerate() {
+            match bit {
+                CnfBit::Constant(value) => writeln!(w, "c output {i} = {}", u8::from(*value))?,
+                CnfBit::Literal(lit) => writeln!(w, "c output {i} = {lit}")?,
+            }
+        }
+        writeln!(w, "p cnf {} {}", self.num_vars(), self.clauses.len())?;
+        for clause in &self.clauses {
+            for lit in clause {
+                write!(w, "{lit} ")?;
+            }
+            writeln!(w, "0")?;
+        }
+        Ok(())
+    }
+}
+
+struct CnfBuilder {
+    next_var: i32,
+    gates: Vec<Gate>,
+    clauses: Vec<Vec<i32>>,
+}
+
+impl CnfBuilder {
+    /// Define a bit as a function `f` of `inputs` with constants propagated.
+    fn gate(&mut self, inputs: &[CnfBit], f: fn(&[bool]) -> bool) -> CnfBit {
+        let lits: Vec<i32> = inputs
+            .iter()
+            .filter_map(|bit| match *bit {
+                CnfBit::Literal(lit) => Some(lit),
+                CnfBit::Constant(_) => None,
+            })
+            .collect();
+        let mut table = 0u8;
+        let mut values = Vec::with_capacity(inputs.len());
+        for j in 0..1 << lits.len() {
+            values.clear();
+            let mut k = 0;
+            for bit in inputs {
+                values.push(match *bit {
+                    CnfBit::Constant(value) => value,
+                    CnfBit::Literal(_) => {
+                        k += 1;
+                        (j >> (k - 1)) & 1 == 1
+                    }
+                });
+            }
+            table |= u8::from(f(&values)) << j;
+        }
+
+        let full = u8::MAX >> (8 - (1 << lits.len()));
+        match (lits.len(), table) {
+            (_, 0) => return CnfBit::Constant(false),
+            (_, t) if t == full => return CnfBit::Constant(true),
+            (1, 0b10) => return CnfBit::Literal(lits[0]),
+            (1, 0b01) => return CnfBit::Literal(-lits[0]),
+            _ => {}
+        }
+
+        let var = self.next_var;
+        self.next_var += 1;
+        for j in 0..1 << lits.len() {
+            let mut clause: Vec<i32> = lits
+                .iter()
+                .enumerate()
+                .map(|(k, &lit)| if (j >> k) & 1 == 1 { -lit } else { lit })
+                .collect();
+            clause.push(if (table >> j) & 1 == 1 { var } else { -var });
+            self.clauses.push(clause);
+        }
+        self.gates.push(Gate {
+            inputs: lits,
+            table,
+        });
+        CnfBit::Literal(var)
+    }
+}
+
+impl Builder for CnfBuilder {
+    type Bit = CnfBit;
+
+    fn not(&mut self, a: &CnfBit) -> CnfBit {
+        match *a {
+            CnfBit::Constant(value) => CnfBit::Constant(!value),
+            CnfBit::Literal(lit) => CnfBit::Literal(-lit),
+        }
+    }
+
+    fn xor(&mut self, a: &CnfBit, b: &CnfBit) -> CnfBit {
+        self.gate(&[*a, *b], |v| v[0] ^ v[1])
+    }
+
+    fn chi(&mut self, a: &CnfBit, b: &CnfBit, c: &CnfBit) -> CnfBit {
+        self.gate(&[*a, *b, *c], |v| v[0] ^ (!v[1] & v[2]))
+    }
+}
+
+/// Encode Keccak-p with `ROUNDS` rounds over lanes of type `L` as a CNF formula.
+///
+/// Every XOR of two bits and every chi output bit which does not reduce to a constant
+/// or to a literal is assigned a new auxiliary variable.
+///
+/// # Panics
+/// If length of `input` is not equal to `25 * L::BITS`.
+#[must_use]
+pub fn cnf<L: LaneSize, const ROUNDS: usize>(input: &[InputBit]) -> Cnf {
+    let (w, rounds) = params::<L, ROUNDS>(input);
+    let mut builder = CnfBuilder {
+        next_var: 1,
+        gates: Vec::new(),
+        clauses: Vec::new(),
+    };
+    let state = input
+        .iter()
+        .map(|bit| match *bit {
+            InputBit::Variable => {
+                builder.next_var += 1;
+                CnfBit::Literal(builder.next_var - 1)
+            }
+            InputBit::Constant(value) => CnfBit::Constant(value),
+        })
+        .collect();
+    let num_inputs = input
+        .iter()
+        .filter(|&&bit| bit == InputBit::Variable)
+        .count();
+    let outputs = permute(&mut builder, state, w, rounds);
+    Cnf {
+        width: PLEN * w,
+        rounds: ROUNDS,
+        num_inputs,
+        gates: builder.gates,
+        clauses: builder.clauses,
+        outputs,
+    }
+}
@@ -1,9 +1,123 @@
 //! Keccak backend implementations.
-use crate::consts::F1600_ROUNDS;
+use crate::bytes::StateBytes;
+use crate::consts::*;
 use crate::types::*;
+use core::fmt;
 #[cfg(feature = "parallel")]
 use hybrid_array::ArraySize;
 
+/// Select the `p1600` getter instantiated with the number of rounds known only at runtime.
+macro_rules! dispatch_rounds {
+    ($rounds:expr, $get:ident) => {
+        dispatch_rounds!(
+            $rounds, $get,
+            [
+                0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16
+                17 18 19 20 21 22 23 24 25 26 27 28 29 30 31 32
+            ]
+        )
+    };
+    ($rounds:expr, $get:ident, [$($n:literal)*]) => {
+        match $rounds {
+            $($n => Ok(Self::$get::<$n>()),)*
+            rounds => Err(InvalidRounds { rounds }),
+        }
+    };
+}
+
+/// Define byte-oriented state access methods of [`Backend`] for a state width.
+macro_rules! snp_methods {
+    (
+        $state:ident, $byte_len:literal, $add:ident, $overwrite:ident, $zeroes:ident,
+        $extract:ident, $extract_and_add:ident
+    ) => {
+        #[doc = concat!("XOR `data` into [`", stringify!($state), "`] starting at the byte `offset`.")]
+        ///
+        /// See [`StateBytes::add_bytes`] for more information.
+        ///
+        /// # Panics
+        #[doc = concat!("If `offset + data.len()` is bigger than ", $byte_len, ".")]
+        #[inline]
+        fn $add(state: &mut $state, data: &[u8], offset: usize) {
+            state.add_bytes(data, offset);
+        }
+
+        #[doc = concat!("Overwrite bytes of [`", stringify!($state), "`] starting at the byte `offset` with `data`.")]
+        ///
+        /// See [`StateBytes::overwrite_bytes`] for more information.
+        ///
+        /// # Panics
+        #[doc = concat!("If `offset + data.len()` is bigger than ", $byte_len, ".")]
+        #[inline]
+        fn $overwrite(state: &mut $state, data: &[u8], offset: usize) {
+            state.overwrite_bytes(data, offset);
+        }
+
+        #[doc = concat!("Overwrite the first `byte_count` bytes of [`", stringify!($state), "`] with zeroes.")]
+        ///
+        /// See [`StateBytes::overwrite_with_zeroes`] for more information.
+        ///
+        /// # Panics
+        #[doc = concat!("If `byte_count` is bigger than ", $byte_len, ".")]
+        #[inline]
+        fn $zeroes(state: &mut $state, byte_count: usize) {
+            state.overwrite_with_zeroes(byte_count);
+        }
+
+        #[doc = concat!("Copy bytes of [`", stringify!($state), "`] starting at the byte `offset` into `data`.")]
+        ///
+        /// See [`StateBytes::extract_bytes`] for more information.
+        ///
+        /// # Panics
+        #[doc = concat!("If `offset + data.len()` is bigger than ", $byte_len, ".")]
+        #[inline]
+        fn $extract(state: &$state, data: &mut [u8], offset: usize) {
+            state.extract_bytes(data, offset);
+        }
+
+        #[doc = concat!("XOR bytes of [`", stringify!($state), "`] starting at the byte `offset` with `input`")]
+        /// and write the result into `output`.
+        ///
+        /// See [`StateBytes::extract_and_add_bytes`] for more information.
+        ///
+        /// # Panics
+        /// If `input` and `output` have different lengths or if `offset + input.len()`
+        #[doc = concat!("is bigger than ", $byte_len, ".")]
+        #[inline]
+        fn $extract_and_add(state: &$state, input: &[u8], output: &mut [u8], offset: usize) {
+            state.extract_and_add_bytes(input, output, offset);
+        }
+    };
+}
+
+/// Use parallel `p200`, `p400`, and `p800` functions of the software backend.
+///
+/// Backends with SIMD support only for [`State1600`] delegate to SWAR-based
+/// implementations of the smaller permutations.
+#[cfg(feature = "parallel")]
+macro_rules! impl_soft_small_par {
+    () => {
+        type ParSize200 = <super::soft::Backend as super::Backend>::ParSize200;
+        type ParSize400 = <super::soft::Backend as super::Backend>::ParSize400;
+        type ParSize800 = <super::soft::Backend as super::Backend>::ParSize800;
+
+        #[inline]
+        fn get_par_p200<const ROUNDS: usize>() -> crate::types::ParFn200<Self> {
+            <super::soft::Backend as super::Backend>::get_par_p200::<ROUNDS>()
+        }
+
+        #[inline]
+        fn get_par_p400<const ROUNDS: usize>() -> crate::types::ParFn400<Self> {
+            <super::soft::Backend as super::Backend>::get_par_p400::<ROUNDS>()
+        }
+
+        #[inline]
+        fn get_par_p800<const ROUNDS: usize>() -> crate::types::ParFn800<Self> {
+            <super::soft::Backend as super::Backend>::get_par_p800::<ROUNDS>()
+        }
+    };
+}
+
 #[cfg(target_arch = "aarch64")]
 pub(crate) mod aarch64_sha3;
 #[cfg(any(
@@ -13,6 +127,54 @@ pub(crate) mod aarch64_sha3;
 ))]
 pub(crate) mod simd;
 pub(crate) mod soft;
+#[cfg(any(
+    keccak_backend = "soft_interleaved",
+    all(target_pointer_width = "32", not(target_family = "wasm")),
+))]
+pub(crate) mod soft_interleaved;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub(crate) mod x86_avx2;
+#[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), keccak_avx512))]
+#[clippy::msrv = "1.89"]
+pub(crate) mod x86_avx512;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub(crate) mod x86_sse2;
+
+cfg_if::cfg_if! {
+    if #[cfg(all(target_pointer_width = "32", not(target_family = "wasm")))] {
+        /// Scalar backend used by default on the current target.
+        pub(crate) use soft_interleaved::Backend as ScalarBackend;
+    } else {
+        /// Scalar backend used by default on the current target.
+        pub(crate) use soft::Backend as ScalarBackend;
+    }
+}
+
+/// Error returned for a number of Keccak-p\[1600\] rounds bigger than [`MAX_RUNTIME_ROUNDS`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct InvalidRounds {
+    rounds: usize,
+}
+
+impl InvalidRounds {
+    /// Requested number of rounds.
+    #[must_use]
+    pub fn rounds(&self) -> usize {
+        self.rounds
+    }
+}
+
+impl fmt::Display for InvalidRounds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid number of Keccak-p[1600] rounds: {} (maximum is {MAX_RUNTIME_ROUNDS})",
+            self.rounds,
+        )
+    }
+}
+
+impl core::error::Error for InvalidRounds {}
 
 /// Trait used to define a closure which operates over Keccak backends.
 pub trait BackendClosure {
@@ -22,21 +184,114 @@ pub trait BackendClosure {
 
 /// Trait implemented by a Keccak backend.
 pub trait Backend {
+    /// Parallelism width supported by the backend for [`State200`].
+    #[cfg(feature = "parallel")]
+    type ParSize200: ArraySize;
+    /// Parallelism width supported by the backend for [`State400`].
+    #[cfg(feature = "parallel")]
+    type ParSize400: ArraySize;
+    /// Parallelism width supported by the backend for [`State800`].
+    #[cfg(feature = "parallel")]
+    type ParSize800: ArraySize;
     /// Parallelism width supported by the backend for [`State1600`].
     #[cfg(feature = "parallel")]
     type ParSize1600: ArraySize;
 
+    /// Get scalar `p200` function with the specified number of rounds.
+    #[inline]
+    #[must_use]
+    fn get_p200<const ROUNDS: usize>() -> Fn200 {
+        soft::keccak_p::<u8, ROUNDS>
+    }
+
+    /// Get parallel `p200` function with the specified number of rounds.
+    #[cfg(feature = "parallel")]
+    #[inline]
+    #[must_use]
+    fn get_par_p200<const ROUNDS: usize>() -> ParFn200<Self> {
+        |par_state| par_state.iter_mut().for_each(Self::get_p200::<ROUNDS>())
+    }
+
+    /// Get scalar `f200` function.
+    #[must_use]
+    fn get_f200() -> Fn200 {
+        Self::get_p200::<F200_ROUNDS>()
+    }
+
+    /// Get parallel `f200` function.
+    #[cfg(feature = "parallel")]
+    #[inline]
+    #[must_use]
+    fn get_par_f200() -> ParFn200<Self> {
+        Self::get_par_p200::<F200_ROUNDS>()
+    }
+
+    /// Get scalar `p400` function with the specified number of rounds.
+    #[inline]
+    #[must_use]
+    fn get_p400<const ROUNDS: usize>() -> Fn400 {
+        soft::keccak_p::<u16, ROUNDS>
+    }
+
+    /// Get parallel `p400` function with the specified number of rounds.
+    #[cfg(feature = "parallel")]
+    #[inline]
+    #[must_use]
+    fn get_par_p400<const ROUNDS: usize>() -> ParFn400<Self> {
+        |par_state| par_state.iter_mut().for_each(Self::get_p400::<ROUNDS>())
+    }
+
+    /// Get scalar `f400` function.
+    #[must_use]
+    fn get_f400() -> Fn400 {
+        Self::get_p400::<F400_ROUNDS>()
+    }
+
+    /// Get parallel `f400` function.
+    #[cfg(feature = "parallel")]
+    #[inline]
+    #[must_use]
+    fn get_par_f400() -> ParFn400<Self> {
+        Self::get_par_p400::<F400_ROUNDS>()
+    }
+
+    /// Get scalar `p800` function with the specified number of rounds.
+    #[inline]
+    #[must_use]
+    fn get_p800<const ROUNDS: usize>() -> Fn800 {
+        soft::keccak_p::<u32, ROUNDS>
+    }
+
+    /// Get parallel `p800` function with the specified number of rounds.
+    #[cfg(feature = "parallel")]
+    #[inline]
+    #[must_use]
+    fn get_par_p800<const ROUNDS: usize>() -> ParFn800<Self> {
+        |par_state| par_state.iter_mut().for_each(Self::get_p800::<ROUNDS>())
+    }
+
+    /// Get scalar `f800` function.
+    #[must_use]
+    fn get_f800() -> Fn800 {
+        Self::get_p800::<F800_ROUNDS>()
+    }
+
+    /// Get parallel `f800` function.
+    #[cfg(feature = "parallel")]
+    #[inline]
+    #[must_use]
+    fn get_par_f800() -> ParFn800<Self> {
+        Self::get_par_p800::<F800_ROUNDS>()
+    }
+
     /// Get scalar `p1600` function with the specified number of rounds.
     ///
-    /// # Panics
-    /// If `ROUNDS` is bigger than [`F1600_ROUNDS`].
+    /// If `ROUNDS` is bigger than [`F1600_ROUNDS`], the rounds of Keccak-f\[1600\] are
+    /// preceded by rounds with negative indices (see [`round_constants`]).
     #[must_use]
     fn get_p1600<const ROUNDS: usize>() -> Fn1600;
 
     /// Get parallel `p1600` function with the specified number of rounds.
-    ///
-    /// # Panics
-    /// If `ROUNDS` is bigger than [`F1600_ROUNDS`].
     #[cfg(feature = "parallel")]
     #[inline]
     #[must_use]
@@ -57,4 +312,157 @@ pub trait Backend {
     fn get_par_f1600() -> ParFn1600<Self> {
         Self::get_par_p1600::<F1600_ROUNDS>()
     }
+
+    /// Get scalar `p1600` function with the number of rounds specified at runtime.
+    ///
+    /// Bigger numbers of rounds are available only through [`Backend::get_p1600`].
+    ///
+    /// # Errors
+    /// If `rounds` is bigger than [`MAX_RUNTIME_ROUNDS`].
+    #[inline]
+    fn get_p1600_rounds(rounds: usize) -> Result<Fn1600, InvalidRounds> {
+        dispatch_rounds!(rounds, get_p1600)
+    }
+
+    /// Get parallel `p1600` function with the number of rounds specified at runtime.
+    ///
+    /// # Errors
+    /// If `rounds` is bigger than [`MAX_RUNTIME_ROUNDS`].
+    #[cfg(feature = "parallel")]
+    #[inline]
+    fn get_par_p1600_rounds(rounds: usize) -> Result<ParFn1600<Self>, InvalidRounds> {
+        dispatch_rounds!(rounds, get_par_p1600)
+    }
+
+    /// Absorb full blocks of `data` into [`State1600`] using `p1600` with the specified
+    /// number of rounds.
+    ///
+    /// Every block is `lane_count` lanes long. It gets XORed into the beginning of the state
+    /// followed by application of the permutation. Absorption stops when less than one block
+    /// of data is left. Returns number of absorbed bytes.
+    ///
+    /// This is an equivalent of XKCP's `KeccakF1600_FastLoop_Absorb` function.
+    /// Backends may override it to keep state in registers between blocks.
+    ///
+    /// # Panics
+    /// If `lane_count` is equal to zero or bigger than 25.
+    #[inline]
+    fn fast_loop_absorb_p1600<const ROUNDS: usize>(
+        state: &mut State1600,
+        lane_count: usize,
+        data: &[u8],
+    ) -> usize {
+        assert!(
+            (1..=PLEN).contains(&lane_count),
+            "invalid lane count: {lane_count}",
+        );
+        let p1600 = Self::get_p1600::<ROUNDS>();
+        let mut blocks = data.chunks_exact(8 * lane_count);
+        for block in &mut blocks {
+            Self::add_bytes1600(state, block, 0);
+            p1600(state);
+        }
+        data.len() - blocks.remainder().len()
+    }
+
+    /// Absorb full blocks of `data` into [`State1600`] using `f1600`.
+    ///
+    /// See [`Backend::fast_loop_absorb_p1600`] for more information.
+    ///
+    /// # Panics
+    /// If `lane_count` is equal to zero or bigger than 25.
+    #[inline]
+    fn fast_loop_absorb_f1600(state: &mut State1600, lane_count: usize, data: &[u8]) -> usize {
+        Self::fast_loop_absorb_p1600::<F1600_ROUNDS>(state, lane_count, data)
+    }
+
+    /// XOR `data` into [`State1600`] starting at the byte `offset`.
+    ///
+    /// See [`StateBytes::add_bytes`] for more information.
+    ///
+    /// # Panics
+    /// If `offset + data.len()` is bigger than 200.
+    #[inline]
+    fn add_bytes1600(state: &mut State1600, data: &[u8], offset: usize) {
+        state.add_bytes(data, offset);
+    }
+
+    /// Overwrite bytes of [`State1600`] starting at the byte `offset` with `data`.
+    ///
+    /// See [`StateBytes::overwrite_bytes`] for more information.
+    ///
+    /// # Panics
+    /// If `offset + data.len()` is bigger than 200.
+    #[inline]
+    fn overwrite_bytes1600(state: &mut State1600, data: &[u8], offset: usize) {
+        state.overwrite_bytes(data, offset);
+    }
+
+    /// Overwrite the first `byte_count` bytes of [`State1600`] with zeroes.
+    ///
+    /// See [`StateBytes::overwrite_with_zeroes`] for more information.
+    ///
+    /// # Panics
+    /// If `byte_count` is bigger than 200.
+    #[inline]
+    fn overwrite_with_zeroes1600(state: &mut State1600, byte_count: usize) {
+        state.overwrite_with_zeroes(byte_count);
+    }
+
+    /// Copy bytes of [`State1600`] starting at the byte `offset` into `data`.
+    ///
+    /// See [`StateBytes::extract_bytes`] for more information.
+    ///
+    /// # Panics
+    /// If `offset + data.len()` is bigger than 200.
+    #[inline]
+    fn extract_bytes1600(state: &State1600, data: &mut [u8], offset: usize) {
+        state.extract_bytes(data, offset);
+    }
+
+    /// XOR bytes of [`State1600`] starting at the byte `offset` with `input` and write
+    /// the result into `output`.
+    ///
+    /// See [`StateBytes::extract_and_add_bytes`] for more information.
+    ///
+    /// # Panics
+    /// If `input` and `output` have different lengths or if `offset + input.len()`
+    /// is bigger than 200.
+    #[inline]
+    fn extract_and_add_bytes1600(
+        state: &State1600,
+        input: &[u8],
+        output: &mut [u8],
+        offset: usize,
+    ) {
+        state.extract_and_add_bytes(input, output, offset);
+    }
+
+    snp_methods!(
+        State200,
+        25,
+        add_bytes200,
+        overwrite_bytes200,
+        overwrite_with_zeroes200,
+        extract_bytes200,
+        extract_and_add_bytes200
+    );
+    snp_methods!(
+        State400,
+        50,
+        add_bytes400,
+        overwrite_bytes400,
+        overwrite_with_zeroes400,
+        extract_bytes400,
+        extract_and_add_bytes400
+    );
+    snp_methods!(
+        State800,
+        100,
+        add_bytes800,
+        overwrite_bytes800,
+        overwrite_with_zeroes800,
+        extract_bytes800,
+        extract_and_add_bytes800
+    );
 }
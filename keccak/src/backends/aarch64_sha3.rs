@@ -3,7 +3,7 @@
 // TODO(tarcieri): remove when MSRV 1.87
 #![allow(unsafe_op_in_unsafe_fn)]
 
-use crate::consts::{PLEN, RC};
+use crate::consts::{PLEN, rc1600};
 use crate::types::Fn1600;
 #[cfg(feature = "parallel")]
 use crate::types::ParFn1600;
@@ -16,20 +16,32 @@ use hybrid_array::{Array, typenum::U2};
 pub(crate) struct Backend;
 
 impl super::Backend for Backend {
+    #[cfg(feature = "parallel")]
+    impl_soft_small_par!();
     #[cfg(feature = "parallel")]
     type ParSize1600 = U2;
 
     #[inline]
     fn get_p1600<const ROUNDS: usize>() -> Fn1600 {
         // SAFETY: the backend is used only after required target feature checks
-        |state| unsafe { p1600_armv8_sha3(state, ROUNDS) }
+        |state| unsafe { p1600_armv8_sha3(state, rc1600::<ROUNDS>()) }
+    }
+
+    #[inline]
+    fn fast_loop_absorb_p1600<const ROUNDS: usize>(
+        state: &mut [u64; PLEN],
+        lane_count: usize,
+        data: &[u8],
+    ) -> usize {
+        // SAFETY: the backend is used only after required target feature checks
+        unsafe { fast_loop_absorb_armv8_sha3(state, lane_count, data, rc1600::<ROUNDS>()) }
     }
 
     #[cfg(feature = "parallel")]
     #[inline]
     fn get_par_p1600<const ROUNDS: usize>() -> ParFn1600<Self> {
         // SAFETY: the backend is used only after required target feature checks
-        |Array(state)| unsafe { p1600_armv8_sha3_times2(state, ROUNDS) }
+        |Array(state)| unsafe { p1600_armv8_sha3_times2(state, rc1600::<ROUNDS>()) }
     }
 }
 
@@ -39,11 +51,11 @@ impl super::Backend for Backend {
 /// Adapted from the Keccak-f1600 implementation in the XKCP/K12.
 /// see <https://github.com/XKCP/K12/blob/df6a21e6d1f34c1aa36e8d702540899c97dba5a0/lib/ARMv8Asha3/KeccakP-1600-ARMv8Asha3.S#L69>
 #[target_feature(enable = "sha3")]
-unsafe fn p1600_armv8_sha3(state: &mut [u64; PLEN], round_count: usize) {
+unsafe fn p1600_armv8_sha3(state: &mut [u64; PLEN], round_consts: &[u64]) {
     let mut s = [*state, Default::default()];
     // SAFETY: both functions have the same safety invariants, namely they require the `sha3`
     // target feature is available, and the caller is responsible for ensuring support
-    unsafe { p1600_armv8_sha3_times2(&mut s, round_count) };
+    unsafe { p1600_armv8_sha3_times2(&mut s, round_consts) };
     *state = s[0];
 }
 
@@ -54,20 +66,11 @@ unsafe fn p1600_armv8_sha3(state: &mut [u64; PLEN], round_count: usize) {
 ///
 /// <https://github.com/XKCP/K12/blob/df6a21e/lib/ARMv8Asha3/KeccakP-1600-ARMv8Asha3.S#L69>
 #[target_feature(enable = "sha3")]
-unsafe fn p1600_armv8_sha3_times2(state: &mut [[u64; PLEN]; 2], round_count: usize) {
-    assert!(
-        round_count <= 24,
-        "invalid round count greater than 24: {round_count}",
-    );
-
+unsafe fn p1600_armv8_sha3_times2(state: &mut [[u64; PLEN]; 2], round_consts: &[u64]) {
     let mut s: [uint64x2_t; PLEN] =
         array::from_fn(|i| vcombine_u64(vcreate_u64(state[0][i]), vcreate_u64(state[1][i])));
 
-    for &rc in &RC[(24 - round_count)..] {
-        let (d0, d1, d2, d3, d4) = theta(&s);
-        let t = rho_pi(&s, d0, d1, d2, d3, d4);
-        s = chi_iota(&t, rc);
-    }
+    rounds(&mut s, round_consts);
 
     for i in 0..PLEN {
         state[0][i] = vgetq_lane_u64::<0>(s[i]);
@@ -75,6 +78,49 @@ unsafe fn p1600_armv8_sha3_times2(state: &mut [[u64; PLEN]; 2], round_count: usi
     }
 }
 
+/// Absorb full blocks of `data` into `state` with `FEAT_SHA3` keeping the state
+/// in registers between blocks.
+#[target_feature(enable = "sha3")]
+unsafe fn fast_loop_absorb_armv8_sha3(
+    state: &mut [u64; PLEN],
+    lane_count: usize,
+    data: &[u8],
+    round_consts: &[u64],
+) -> usize {
+    assert!(
+        (1..=PLEN).contains(&lane_count),
+        "invalid lane count: {lane_count}",
+    );
+
+    let zero = vdupq_n_u64(0);
+    let mut s: [uint64x2_t; PLEN] = array::from_fn(|i| vsetq_lane_u64::<0>(state[i], zero));
+
+    let mut blocks = data.chunks_exact(8 * lane_count);
+    for block in &mut blocks {
+        for (v, lane) in s.iter_mut().zip(block.chunks_exact(8)) {
+            let lane = u64::from_le_bytes(lane.try_into().expect("lane is 8 bytes long"));
+            *v = veorq_u64(*v, vsetq_lane_u64::<0>(lane, zero));
+        }
+        rounds(&mut s, round_consts);
+    }
+
+    for i in 0..PLEN {
+        state[i] = vgetq_lane_u64::<0>(s[i]);
+    }
+
+    data.len() - blocks.remainder().len()
+}
+
+/// Apply Keccak-p1600 rounds with the constants `round_consts` to a pair of states.
+#[target_feature(enable = "sha3")]
+unsafe fn rounds(s: &mut [uint64x2_t; PLEN], round_consts: &[u64]) {
+    for &rc in round_consts {
+        let (d0, d1, d2, d3, d4) = theta(s);
+        let t = rho_pi(s, d0, d1, d2, d3, d4);
+        *s = chi_iota(&t, rc);
+    }
+}
+
 #[target_feature(enable = "sha3")]
 unsafe fn theta(
     s: &[uint64x2_t; 25],
@@ -1,5 +1,6 @@
 /// Backend implementation using the portable SIMD API.
-use super::soft::{LaneSize, keccak_p};
+use super::soft::keccak_p;
+use crate::generic::LaneSize;
 use crate::types::{Fn1600, ParFn1600};
 use core::array;
 use hybrid_array::{Array, typenum};
@@ -12,7 +13,7 @@ use core::simd::u64x4 as u64xN;
 use core::simd::u64x8 as u64xN;
 
 impl LaneSize for u64xN {
-    const KECCAK_F_ROUND_COUNT: usize = crate::consts::F1600_ROUNDS;
+    const BITS: u32 = 64;
 
     fn truncate_rc(rc: u64) -> Self {
         Self::splat(rc)
@@ -27,6 +28,8 @@ impl LaneSize for u64xN {
 pub(crate) struct Backend;
 
 impl super::Backend for Backend {
+    #[cfg(feature = "parallel")]
+    impl_soft_small_par!();
     #[cfg(all(feature = "parallel", keccak_backend = "simd128"))]
     type ParSize1600 = typenum::U2;
     #[cfg(all(feature = "parallel", keccak_backend = "simd256"))]
@@ -1,53 +1,15 @@
+use crate::generic::{LaneSize, keccak_f_round_count};
+#[cfg(feature = "parallel")]
+use crate::types::{ParFn200, ParFn400, ParFn800};
 use crate::{consts::*, types::Fn1600};
-use core::ops::{BitAnd, BitAndAssign, BitXor, BitXorAssign, Not};
 #[cfg(feature = "parallel")]
-use hybrid_array::typenum::U1;
-
-/// Keccak is a permutation over an array of lanes which comprise the sponge
-/// construction.
-pub trait LaneSize:
-    Copy
-    + Clone
-    + Default
-    + PartialEq
-    + BitAndAssign
-    + BitAnd<Output = Self>
-    + BitXorAssign
-    + BitXor<Output = Self>
-    + Not<Output = Self>
-{
-    /// Number of rounds of the Keccak-f permutation.
-    const KECCAK_F_ROUND_COUNT: usize;
-
-    /// Truncate function.
-    fn truncate_rc(rc: u64) -> Self;
-
-    /// Rotate left function.
-    #[must_use]
-    fn rotate_left(self, n: u32) -> Self;
-}
-
-macro_rules! impl_lanesize {
-    ($type:ty, $round:expr) => {
-        impl LaneSize for $type {
-            const KECCAK_F_ROUND_COUNT: usize = $round;
+use hybrid_array::{
+    Array,
+    typenum::{U1, U2, U4, U8},
+};
 
-            #[allow(clippy::cast_possible_truncation, trivial_numeric_casts)]
-            fn truncate_rc(rc: u64) -> Self {
-                rc as Self
-            }
-
-            fn rotate_left(self, n: u32) -> Self {
-                self.rotate_left(n)
-            }
-        }
-    };
-}
-
-impl_lanesize!(u8, F200_ROUNDS);
-impl_lanesize!(u16, F400_ROUNDS);
-impl_lanesize!(u32, F800_ROUNDS);
-impl_lanesize!(u64, F1600_ROUNDS);
+#[cfg(feature = "parallel")]
+mod swar;
 
 #[rustfmt::skip]
 macro_rules! unroll5 {
@@ -106,16 +68,26 @@ macro_rules! unroll24 {
 
 /// Generic Keccak-p sponge function.
 ///
-/// # Panics
-/// If the `ROUNDS` is greater than `L::KECCAK_F_ROUND_COUNT`.
-#[allow(non_upper_case_globals, unused_assignments)]
+/// This is the implementation of [`crate::generic::keccak_p`].
+///
+/// If `ROUNDS` is bigger than `keccak_f_round_count::<L>()`, the Keccak-f rounds are preceded
+/// by rounds with negative indices.
 pub(crate) fn keccak_p<L: LaneSize, const ROUNDS: usize>(state: &mut [L; PLEN]) {
+    keccak_p_inner::<L, ROUNDS>(state);
+}
+
+/// Body of [`keccak_p`] which gets inlined into the functions compiled with
+/// additional target features.
+#[inline(always)]
+#[allow(non_upper_case_globals, unused_assignments)]
+fn keccak_p_inner<L: LaneSize, const ROUNDS: usize>(state: &mut [L; PLEN]) {
     // https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.202.pdf#page=25
     // "the rounds of KECCAK-p[b, nr] match the last rounds of KECCAK-f[b]"
-    let round_consts = RC[..L::KECCAK_F_ROUND_COUNT]
-        .last_chunk::<ROUNDS>()
-        .expect("Number of rounds greater than `KECCAK_F_ROUND_COUNT` is not supported!")
-        .map(L::truncate_rc);
+    let round_consts =
+        const { round_constants::<ROUNDS>(keccak_f_round_count::<L>()) }.map(L::truncate_rc);
+
+    #[cfg(keccak_backend_soft = "lane_complementing")]
+    complement_lanes(state);
 
     // Not unrolling this loop results in a much smaller function, plus
     // it positively influences performance due to the smaller load on I-cache
@@ -131,7 +103,7 @@ pub(crate) fn keccak_p<L: LaneSize, const ROUNDS: usize>(state: &mut [L; PLEN])
 
         unroll5!(x, {
             let t1 = array[(x + 4) % 5];
-            let t2 = array[(x + 1) % 5].rotate_left(1);
+            let t2 = array[(x + 1) % 5].rotate_left(1 % L::BITS);
             unroll5!(y, {
                 state[5 * y + x] ^= t1 ^ t2;
             });
@@ -141,11 +113,14 @@ pub(crate) fn keccak_p<L: LaneSize, const ROUNDS: usize>(state: &mut [L; PLEN])
         let mut last = state[1];
         unroll24!(x, {
             array[0] = state[PI[x]];
-            state[PI[x]] = last.rotate_left(RHO[x]);
+            state[PI[x]] = last.rotate_left(RHO[x] % L::BITS);
             last = array[0];
         });
 
         // Chi
+        #[cfg(keccak_backend_soft = "lane_complementing")]
+        chi_lane_complementing(state);
+        #[cfg(not(keccak_backend_soft = "lane_complementing"))]
         unroll5!(y_step, {
             let y = 5 * y_step;
 
@@ -161,17 +136,119 @@ pub(crate) fn keccak_p<L: LaneSize, const ROUNDS: usize>(state: &mut [L; PLEN])
         // Iota
         state[0] ^= rc;
     }
+
+    #[cfg(keccak_backend_soft = "lane_complementing")]
+    complement_lanes(state);
+}
+
+/// Complement lanes at the positions used by the lane complementing transform.
+///
+/// The transform is an involution, so the same function is used on input and output.
+#[cfg(keccak_backend_soft = "lane_complementing")]
+#[inline(always)]
+fn complement_lanes<L: LaneSize>(state: &mut [L; PLEN]) {
+    for i in [1, 2, 8, 12, 17, 20] {
+        state[i] = !state[i];
+    }
+}
+
+/// Chi step applied to a state with complemented lanes (see [`complement_lanes`]).
+///
+/// Based on the "bebigokimisa" pattern from the [Keccak implementation overview],
+/// which reduces number of NOT operations from 25 to 5 per round.
+///
+/// [Keccak implementation overview]: https://keccak.team/files/Keccak-implementation-3.2.pdf
+#[cfg(keccak_backend_soft = "lane_complementing")]
+#[inline(always)]
+fn chi_lane_complementing<L: LaneSize>(s: &mut [L; PLEN]) {
+    let [a, e, i, o, u] = [s[0], s[1], s[2], s[3], s[4]];
+    s[0] = a ^ (e | i);
+    s[1] = e ^ (!i | o);
+    s[2] = i ^ (o & u);
+    s[3] = o ^ (u | a);
+    s[4] = u ^ (a & e);
+
+    let [a, e, i, o, u] = [s[5], s[6], s[7], s[8], s[9]];
+    s[5] = a ^ (e | i);
+    s[6] = e ^ (i & o);
+    s[7] = i ^ (o | !u);
+    s[8] = o ^ (u | a);
+    s[9] = u ^ (a & e);
+
+    let [a, e, i, o, u] = [s[10], s[11], s[12], s[13], s[14]];
+    let not_o = !o;
+    s[10] = a ^ (e | i);
+    s[11] = e ^ (i & o);
+    s[12] = i ^ (not_o & u);
+    s[13] = not_o ^ (u | a);
+    s[14] = u ^ (a & e);
+
+    let [a, e, i, o, u] = [s[15], s[16], s[17], s[18], s[19]];
+    let not_o = !o;
+    s[15] = a ^ (e & i);
+    s[16] = e ^ (i | o);
+    s[17] = i ^ (not_o | u);
+    s[18] = not_o ^ (u & a);
+    s[19] = u ^ (a | e);
+
+    let [a, e, i, o, u] = [s[20], s[21], s[22], s[23], s[24]];
+    let not_e = !e;
+    s[20] = a ^ (not_e & i);
+    s[21] = not_e ^ (i | o);
+    s[22] = i ^ (o & u);
+    s[23] = o ^ (u | a);
+    s[24] = u ^ (a & e);
 }
 
 /// Default backend based on software implementation.
 pub(crate) struct Backend;
 
 impl super::Backend for Backend {
+    #[cfg(feature = "parallel")]
+    type ParSize200 = U8;
+    #[cfg(feature = "parallel")]
+    type ParSize400 = U4;
+    #[cfg(feature = "parallel")]
+    type ParSize800 = U2;
     #[cfg(feature = "parallel")]
     type ParSize1600 = U1;
 
+    #[cfg(feature = "parallel")]
+    #[inline]
+    fn get_par_p200<const ROUNDS: usize>() -> ParFn200<Self> {
+        |Array(states)| swar::keccak_p200_x8::<ROUNDS>(states)
+    }
+
+    #[cfg(feature = "parallel")]
+    #[inline]
+    fn get_par_p400<const ROUNDS: usize>() -> ParFn400<Self> {
+        |Array(states)| swar::keccak_p400_x4::<ROUNDS>(states)
+    }
+
+    #[cfg(feature = "parallel")]
+    #[inline]
+    fn get_par_p800<const ROUNDS: usize>() -> ParFn800<Self> {
+        |Array(states)| swar::keccak_p800_x2::<ROUNDS>(states)
+    }
+
     #[inline]
     fn get_p1600<const ROUNDS: usize>() -> Fn1600 {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        if x86_bmi1::get() {
+            // SAFETY: we checked target feature availability above
+            return |state| unsafe { keccak_p_bmi1::<ROUNDS>(state) };
+        }
+
         keccak_p::<u64, ROUNDS>
     }
 }
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+cpufeatures::new!(x86_bmi1, "bmi1");
+
+/// Keccak-p1600 compiled with BMI1 enabled, which allows compiler to use `andn` in chi.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "bmi1")]
+unsafe fn keccak_p_bmi1<const ROUNDS: usize>(state: &mut [u64; PLEN]) {
+    keccak_p_inner::<u64, ROUNDS>(state);
+}
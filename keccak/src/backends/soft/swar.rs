@@ -0,0 +1,103 @@
+//! SIMD-within-a-register (SWAR) implementation of the small Keccak-p permutations.
+//!
+//! Lanes of several [`State200`], [`State400`] or [`State800`] states are packed into
+//! one 64-bit word, so a single run of the generic software permutation processes
+//! 8, 4 or 2 states at once.
+use super::keccak_p;
+use crate::consts::PLEN;
+use crate::generic::LaneSize;
+use crate::types::{State200, State400, State800};
+use core::ops::{BitAnd, BitAndAssign, BitOr, BitXor, BitXorAssign, Not};
+
+/// 64-bit word holding `64 / W` independent `W`-bit lanes.
+#[derive(Copy, Clone, Default, PartialEq)]
+struct Swar<const W: u32>(u64);
+
+impl<const W: u32> Swar<W> {
+    /// Word with the lowest bit of every packed lane set.
+    const ONES: u64 = u64::MAX / ((1 << W) - 1);
+}
+
+macro_rules! impl_binop {
+    ($trait:ident, $fn:ident, $op:tt) => {
+        impl<const W: u32> $trait for Swar<W> {
+            type Output = Self;
+
+            #[inline(always)]
+            fn $fn(self, rhs: Self) -> Self {
+                Self(self.0 $op rhs.0)
+            }
+        }
+    };
+}
+
+impl_binop!(BitAnd, bitand, &);
+impl_binop!(BitOr, bitor, |);
+impl_binop!(BitXor, bitxor, ^);
+
+impl<const W: u32> BitAndAssign for Swar<W> {
+    #[inline(always)]
+    fn bitand_assign(&mut self, rhs: Self) {
+        self.0 &= rhs.0;
+    }
+}
+
+impl<const W: u32> BitXorAssign for Swar<W> {
+    #[inline(always)]
+    fn bitxor_assign(&mut self, rhs: Self) {
+        self.0 ^= rhs.0;
+    }
+}
+
+impl<const W: u32> Not for Swar<W> {
+    type Output = Self;
+
+    #[inline(always)]
+    fn not(self) -> Self {
+        Self(!self.0)
+    }
+}
+
+impl<const W: u32> LaneSize for Swar<W> {
+    const BITS: u32 = W;
+
+    #[inline(always)]
+    fn truncate_rc(rc: u64) -> Self {
+        Self((rc & ((1 << W) - 1)) * Self::ONES)
+    }
+
+    #[inline(always)]
+    fn rotate_left(self, n: u32) -> Self {
+        let n = n % W;
+        // Bits which wrap around from the top of every packed lane
+        let low = Self::ONES * ((1 << n) - 1);
+        Self(((self.0 << n) & !low) | ((self.0 >> (W - n)) & low))
+    }
+}
+
+macro_rules! impl_keccak_p_swar {
+    ($name:ident, $state:ty, $lane:ty, $n:literal, $w:literal) => {
+        #[doc = concat!("Keccak-p over ", $n, " packed [`", stringify!($state), "`] states.")]
+        #[allow(clippy::cast_possible_truncation)]
+        pub(super) fn $name<const ROUNDS: usize>(states: &mut [$state; $n]) {
+            let mut s = [Swar::<$w>(0); PLEN];
+            for (i, lane) in s.iter_mut().enumerate() {
+                for (k, state) in states.iter().enumerate() {
+                    lane.0 |= u64::from(state[i]) << ($w * k);
+                }
+            }
+
+            keccak_p::<Swar<$w>, ROUNDS>(&mut s);
+
+            for (i, lane) in s.iter().enumerate() {
+                for (k, state) in states.iter_mut().enumerate() {
+                    state[i] = (lane.0 >> ($w * k)) as $lane;
+                }
+            }
+        }
+    };
+}
+
+impl_keccak_p_swar!(keccak_p200_x8, State200, u8, 8, 8);
+impl_keccak_p_swar!(keccak_p400_x4, State400, u16, 4, 16);
+impl_keccak_p_swar!(keccak_p800_x2, State800, u32, 2, 32);
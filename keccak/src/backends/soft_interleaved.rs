@@ -0,0 +1,144 @@
+//! Software backend based on the bit interleaving technique.
+//!
+//! Every 64-bit lane is stored as two 32-bit words, one with the even bits and another
+//! with the odd bits of the lane. This way 64-bit rotations become 32-bit rotations,
+//! which makes this backend efficient on 32-bit targets.
+use super::soft::keccak_p;
+use crate::consts::PLEN;
+use crate::generic::LaneSize;
+use crate::types::Fn1600;
+use core::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+#[cfg(feature = "parallel")]
+use hybrid_array::typenum::U1;
+
+/// Bit-interleaved 64-bit lane.
+#[derive(Copy, Clone, Default, PartialEq)]
+struct Lane {
+    even: u32,
+    odd: u32,
+}
+
+impl Lane {
+    /// Split `x` into even and odd bits.
+    #[inline(always)]
+    #[allow(clippy::cast_possible_truncation)]
+    const fn from_u64(x: u64) -> Self {
+        Self {
+            even: compress(x) as u32,
+            odd: compress(x >> 1) as u32,
+        }
+    }
+
+    /// Merge even and odd bits back into a 64-bit lane.
+    #[inline(always)]
+    const fn to_u64(self) -> u64 {
+        expand(self.even as u64) | (expand(self.odd as u64) << 1)
+    }
+}
+
+/// Gather even bits of `x` into the lower half of the result.
+#[inline(always)]
+const fn compress(x: u64) -> u64 {
+    let mut x = x & 0x5555_5555_5555_5555;
+    x = (x | (x >> 1)) & 0x3333_3333_3333_3333;
+    x = (x | (x >> 2)) & 0x0F0F_0F0F_0F0F_0F0F;
+    x = (x | (x >> 4)) & 0x00FF_00FF_00FF_00FF;
+    x = (x | (x >> 8)) & 0x0000_FFFF_0000_FFFF;
+    (x | (x >> 16)) & 0x0000_0000_FFFF_FFFF
+}
+
+/// Spread the lower half of `x` into even bits of the result.
+#[inline(always)]
+const fn expand(x: u64) -> u64 {
+    let mut x = x & 0x0000_0000_FFFF_FFFF;
+    x = (x | (x << 16)) & 0x0000_FFFF_0000_FFFF;
+    x = (x | (x << 8)) & 0x00FF_00FF_00FF_00FF;
+    x = (x | (x << 4)) & 0x0F0F_0F0F_0F0F_0F0F;
+    x = (x | (x << 2)) & 0x3333_3333_3333_3333;
+    (x | (x << 1)) & 0x5555_5555_5555_5555
+}
+
+macro_rules! impl_binop {
+    ($trait:ident, $fn:ident, $assign_trait:ident, $assign_fn:ident, $op:tt) => {
+        impl $trait for Lane {
+            type Output = Self;
+
+            #[inline(always)]
+            fn $fn(self, rhs: Self) -> Self {
+                Self {
+                    even: self.even $op rhs.even,
+                    odd: self.odd $op rhs.odd,
+                }
+            }
+        }
+
+        impl $assign_trait for Lane {
+            #[inline(always)]
+            fn $assign_fn(&mut self, rhs: Self) {
+                *self = *self $op rhs;
+            }
+        }
+    };
+}
+
+impl_binop!(BitAnd, bitand, BitAndAssign, bitand_assign, &);
+impl_binop!(BitOr, bitor, BitOrAssign, bitor_assign, |);
+impl_binop!(BitXor, bitxor, BitXorAssign, bitxor_assign, ^);
+
+impl Not for Lane {
+    type Output = Self;
+
+    #[inline(always)]
+    fn not(self) -> Self {
+        Self {
+            even: !self.even,
+            odd: !self.odd,
+        }
+    }
+}
+
+impl LaneSize for Lane {
+    const BITS: u32 = 64;
+
+    #[inline(always)]
+    fn truncate_rc(rc: u64) -> Self {
+        Self::from_u64(rc)
+    }
+
+    #[inline(always)]
+    fn rotate_left(self, n: u32) -> Self {
+        if n % 2 == 0 {
+            Self {
+                even: self.even.rotate_left(n / 2),
+                odd: self.odd.rotate_left(n / 2),
+            }
+        } else {
+            Self {
+                even: self.odd.rotate_left(n.div_ceil(2)),
+                odd: self.even.rotate_left(n / 2),
+            }
+        }
+    }
+}
+
+/// Keccak-p1600 over bit-interleaved lanes.
+fn keccak_p_interleaved<const ROUNDS: usize>(state: &mut [u64; PLEN]) {
+    let mut s = state.map(Lane::from_u64);
+    keccak_p::<Lane, ROUNDS>(&mut s);
+    *state = s.map(Lane::to_u64);
+}
+
+/// Software backend based on bit interleaving.
+pub(crate) struct Backend;
+
+impl super::Backend for Backend {
+    #[cfg(feature = "parallel")]
+    impl_soft_small_par!();
+    #[cfg(feature = "parallel")]
+    type ParSize1600 = U1;
+
+    #[inline]
+    fn get_p1600<const ROUNDS: usize>() -> Fn1600 {
+        keccak_p_interleaved::<ROUNDS>
+    }
+}
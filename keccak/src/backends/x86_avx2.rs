@@ -0,0 +1,386 @@
+//! x86 AVX2 intrinsics-based backend.
+
+// TODO: remove when MSRV 1.87
+#![allow(unsafe_op_in_unsafe_fn)]
+
+use crate::consts::{PLEN, rc1600};
+use crate::types::Fn1600;
+#[cfg(feature = "parallel")]
+use crate::types::ParFn1600;
+
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+#[cfg(feature = "parallel")]
+use hybrid_array::{Array, typenum::U4};
+
+/// x86 backend implemented using the AVX2 extension.
+pub(crate) struct Backend;
+
+impl super::Backend for Backend {
+    #[cfg(feature = "parallel")]
+    impl_soft_small_par!();
+    #[cfg(feature = "parallel")]
+    type ParSize1600 = U4;
+
+    #[inline]
+    fn get_p1600<const ROUNDS: usize>() -> Fn1600 {
+        // SAFETY: the backend is used only after required target feature checks
+        |state| unsafe { p1600_avx2(state, rc1600::<ROUNDS>()) }
+    }
+
+    #[cfg(feature = "parallel")]
+    #[inline]
+    fn get_par_p1600<const ROUNDS: usize>() -> ParFn1600<Self> {
+        // SAFETY: the backend is used only after required target feature checks
+        |Array(state)| unsafe { p1600_avx2_times4(state, rc1600::<ROUNDS>()) }
+    }
+}
+
+/// Keccak-p1600 with AVX2.
+///
+/// Similarly to XKCP's `KeccakP-1600-AVX2`, the state is kept in 7 registers:
+///
+/// ```text
+/// a0: A[0]  A[0]  A[0]  A[0]
+/// a1: A[1]  A[2]  A[3]  A[4]
+/// a2: A[15] A[10] A[20] A[5]
+/// a3: A[6]  A[12] A[18] A[24]
+/// a4: A[11] A[7]  A[23] A[19]
+/// a5: A[16] A[22] A[8]  A[14]
+/// a6: A[21] A[17] A[13] A[9]
+/// ```
+///
+/// Columns 1 to 4 are aligned across `a1` and `a3`..`a6` and column 0 lives in `a0` and `a2`,
+/// so theta needs only vertical XORs. Rho uses per-lane variable shifts, while pi is merged
+/// with chi by gathering the chi operands with `vpermq` and `vpblendd`.
+#[target_feature(enable = "avx2")]
+unsafe fn p1600_avx2(state: &mut [u64; PLEN], round_consts: &[u64]) {
+    let ld = |i: [usize; 4]| {
+        let t = i.map(|i| state[i]);
+        // SAFETY: `t` is 32 bytes long
+        unsafe { _mm256_loadu_si256(t.as_ptr().cast()) }
+    };
+    let mut a0 = ld([0, 0, 0, 0]);
+    let mut a1 = ld([1, 2, 3, 4]);
+    let mut a2 = ld([15, 10, 20, 5]);
+    let mut a3 = ld([6, 12, 18, 24]);
+    let mut a4 = ld([11, 7, 23, 19]);
+    let mut a5 = ld([16, 22, 8, 14]);
+    let mut a6 = ld([21, 17, 13, 9]);
+
+    let rho = |v: [u64; 4]| {
+        // SAFETY: `v` is 32 bytes long
+        unsafe { _mm256_loadu_si256(v.as_ptr().cast()) }
+    };
+    let rho1_l = rho([1, 62, 28, 27]);
+    let rho2_l = rho([41, 3, 18, 36]);
+    let rho3_l = rho([44, 43, 21, 14]);
+    let rho4_l = rho([10, 6, 56, 8]);
+    let rho5_l = rho([45, 61, 55, 39]);
+    let rho6_l = rho([2, 15, 25, 20]);
+    let rho1_r = rho([63, 2, 36, 37]);
+    let rho2_r = rho([23, 61, 46, 28]);
+    let rho3_r = rho([20, 21, 43, 50]);
+    let rho4_r = rho([54, 58, 8, 56]);
+    let rho5_r = rho([19, 3, 9, 25]);
+    let rho6_r = rho([62, 49, 39, 44]);
+
+    for &rc in round_consts {
+        // Theta
+        let c14 = xor(xor(a1, a3), xor(xor(a4, a5), a6));
+        let t = xor(a2, perm::<0b01001110>(a2));
+        let c00 = xor(xor(t, _mm256_shuffle_epi32::<0b01001110>(t)), a0);
+        let r14 = rol::<1, 63>(c14);
+        let r00 = rol::<1, 63>(c00);
+        let c41 = perm::<0b10010011>(c14);
+        let d00 = perm::<0>(xor(c41, r14));
+        let d14 = xor(
+            blend::<0b00000011>(c41, c00),
+            blend::<0b11000000>(perm::<0b00111001>(r14), r00),
+        );
+        a0 = xor(a0, d00);
+        a2 = xor(a2, d00);
+        a1 = xor(a1, d14);
+        a3 = xor(a3, d14);
+        a4 = xor(a4, d14);
+        a5 = xor(a5, d14);
+        a6 = xor(a6, d14);
+
+        // Rho
+        let b0 = a0;
+        let b1 = rolv(a1, rho1_l, rho1_r);
+        let b2 = rolv(a2, rho2_l, rho2_r);
+        let b3 = rolv(a3, rho3_l, rho3_r);
+        let b4 = rolv(a4, rho4_l, rho4_r);
+        let b5 = rolv(a5, rho5_l, rho5_r);
+        let b6 = rolv(a6, rho6_l, rho6_r);
+
+        // Pi and chi
+        // A[0]
+        a0 = perm::<0>(chi(b0, b3, perm::<0b11100101>(b3)));
+        // A[1], A[2], A[3], A[4]
+        let t0 = b3;
+        let t1 = blend::<0b11000000>(perm::<0b11111001>(b3), b0);
+        let t2 = blend::<0b00110000>(perm::<0b00101110>(b3), b0);
+        a1 = chi(t0, t1, t2);
+        // A[15], A[10], A[20], A[5]
+        let t0 = perm::<0b10010011>(b1);
+        let t1 = blend::<0b11000000>(
+            blend::<0b00110000>(blend::<0b00001100>(perm::<0b11100111>(b2), b4), b5),
+            b6,
+        );
+        let t2 = blend::<0b11000000>(
+            blend::<0b00110000>(
+                blend::<0b00001100>(b4, perm::<0b11101000>(b6)),
+                perm::<0b11110100>(b5),
+            ),
+            perm::<0b01100100>(b2),
+        );
+        a2 = chi(t0, t1, t2);
+        // A[6], A[12], A[18], A[24]
+        let t0 = perm::<0b00011011>(b6);
+        let t1 = blend::<0b11000000>(
+            blend::<0b00000011>(perm::<0b11101100>(b4), perm::<0b11100101>(b2)),
+            perm::<0b01100100>(b1),
+        );
+        let t2 = blend::<0b00110000>(
+            blend::<0b00001100>(perm::<0b10100100>(b5), perm::<0b11101000>(b2)),
+            perm::<0b11110100>(b1),
+        );
+        a3 = chi(t0, t1, t2);
+        // A[11], A[7], A[23], A[19]
+        let t0 = blend::<0b00111100>(perm::<0b10100101>(b4), perm::<0b11000100>(b2));
+        let t1 = blend::<0b11000000>(
+            blend::<0b00001100>(perm::<0b11000110>(b6), perm::<0b11100000>(b5)),
+            b1,
+        );
+        let t2 = blend::<0b11000000>(
+            blend::<0b00110000>(
+                blend::<0b00001100>(perm::<0b11100111>(b4), b5),
+                perm::<0b11010100>(b1),
+            ),
+            b2,
+        );
+        a4 = chi(t0, t1, t2);
+        // A[16], A[22], A[8], A[14]
+        let t0 = blend::<0b00111100>(perm::<0b10100111>(b2), perm::<0b11001100>(b5));
+        let t1 = blend::<0b11000000>(
+            blend::<0b00110000>(
+                blend::<0b00001100>(b4, perm::<0b11100000>(b2)),
+                perm::<0b11010100>(b5),
+            ),
+            perm::<0b00100100>(b1),
+        );
+        let t2 = blend::<0b11000000>(
+            blend::<0b00110000>(perm::<0b11100001>(b6), b1),
+            perm::<0b01100100>(b4),
+        );
+        a5 = chi(t0, t1, t2);
+        // A[21], A[17], A[13], A[9]
+        let t0 = blend::<0b00111100>(perm::<0b01100110>(b5), perm::<0b11110000>(b4));
+        let t1 = blend::<0b11000000>(
+            blend::<0b00110000>(blend::<0b00001100>(perm::<0b11100111>(b5), b6), b2),
+            perm::<0b10100100>(b1),
+        );
+        let t2 = blend::<0b11000000>(
+            blend::<0b00110000>(
+                blend::<0b00001100>(b2, perm::<0b11101000>(b4)),
+                perm::<0b11000100>(b1),
+            ),
+            b6,
+        );
+        a6 = chi(t0, t1, t2);
+
+        // Iota
+        let rc = [rc; 4];
+        // SAFETY: `rc` is 32 bytes long
+        a0 = xor(a0, unsafe { _mm256_loadu_si256(rc.as_ptr().cast()) });
+    }
+
+    let mut st = |i: [usize; 4], v: __m256i| {
+        let mut t = [0u64; 4];
+        // SAFETY: `t` is 32 bytes long
+        unsafe { _mm256_storeu_si256(t.as_mut_ptr().cast(), v) };
+        for (i, t) in i.into_iter().zip(t) {
+            state[i] = t;
+        }
+    };
+    st([0, 0, 0, 0], a0);
+    st([1, 2, 3, 4], a1);
+    st([15, 10, 20, 5], a2);
+    st([6, 12, 18, 24], a3);
+    st([11, 7, 23, 19], a4);
+    st([16, 22, 8, 14], a5);
+    st([21, 17, 13, 9], a6);
+}
+
+/// Keccak-p1600 with AVX2 with support for 4 parallel states.
+///
+/// Every 256-bit register holds the same lane of all 4 states, so the round function
+/// is a direct translation of the scalar one.
+#[cfg(feature = "parallel")]
+#[target_feature(enable = "avx2")]
+unsafe fn p1600_avx2_times4(state: &mut [[u64; PLEN]; 4], round_consts: &[u64]) {
+    let mut s = [_mm256_setzero_si256(); PLEN];
+    for i in (0..PLEN - 1).step_by(4) {
+        // SAFETY: `state[j][i..]` is at least 32 bytes long
+        let rows = unsafe {
+            [
+                _mm256_loadu_si256(state[0][i..].as_ptr().cast()),
+                _mm256_loadu_si256(state[1][i..].as_ptr().cast()),
+                _mm256_loadu_si256(state[2][i..].as_ptr().cast()),
+                _mm256_loadu_si256(state[3][i..].as_ptr().cast()),
+            ]
+        };
+        s[i..][..4].copy_from_slice(&transpose(rows));
+    }
+    let last = [state[0][24], state[1][24], state[2][24], state[3][24]];
+    // SAFETY: `last` is 32 bytes long
+    s[24] = unsafe { _mm256_loadu_si256(last.as_ptr().cast()) };
+
+    for &rc in round_consts {
+        let d = theta(&s);
+        let b = rho_pi(&s, &d);
+        s = chi_iota(&b, rc);
+    }
+
+    for i in (0..PLEN - 1).step_by(4) {
+        let rows = transpose([s[i], s[i + 1], s[i + 2], s[i + 3]]);
+        for (st, row) in state.iter_mut().zip(rows) {
+            // SAFETY: `st[i..]` is at least 32 bytes long
+            unsafe { _mm256_storeu_si256(st[i..].as_mut_ptr().cast(), row) };
+        }
+    }
+    let mut last = [0u64; 4];
+    // SAFETY: `last` is 32 bytes long
+    unsafe { _mm256_storeu_si256(last.as_mut_ptr().cast(), s[24]) };
+    for (st, lane) in state.iter_mut().zip(last) {
+        st[24] = lane;
+    }
+}
+
+/// Transpose 4x4 matrix of 64-bit words.
+#[cfg(feature = "parallel")]
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn transpose(r: [__m256i; 4]) -> [__m256i; 4] {
+    let t0 = _mm256_unpacklo_epi64(r[0], r[1]);
+    let t1 = _mm256_unpackhi_epi64(r[0], r[1]);
+    let t2 = _mm256_unpacklo_epi64(r[2], r[3]);
+    let t3 = _mm256_unpackhi_epi64(r[2], r[3]);
+    [
+        _mm256_permute2x128_si256::<0x20>(t0, t2),
+        _mm256_permute2x128_si256::<0x20>(t1, t3),
+        _mm256_permute2x128_si256::<0x31>(t0, t2),
+        _mm256_permute2x128_si256::<0x31>(t1, t3),
+    ]
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn xor(a: __m256i, b: __m256i) -> __m256i {
+    _mm256_xor_si256(a, b)
+}
+
+/// Rotate every 64-bit lane left by `L` bits. `R` must be equal to `64 - L`.
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn rol<const L: i32, const R: i32>(a: __m256i) -> __m256i {
+    const { assert!(L + R == 64) };
+    _mm256_or_si256(_mm256_slli_epi64::<L>(a), _mm256_srli_epi64::<R>(a))
+}
+
+/// Rotate every 64-bit lane left by the number of bits specified in the matching lane of `l`.
+/// Lanes of `r` must be equal to `64 - l`.
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn rolv(a: __m256i, l: __m256i, r: __m256i) -> __m256i {
+    _mm256_or_si256(_mm256_sllv_epi64(a, l), _mm256_srlv_epi64(a, r))
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn perm<const IMM: i32>(a: __m256i) -> __m256i {
+    _mm256_permute4x64_epi64::<IMM>(a)
+}
+
+/// Take 64-bit lanes of `b` selected by pairs of set bits in `IMM` and other lanes from `a`.
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn blend<const IMM: i32>(a: __m256i, b: __m256i) -> __m256i {
+    _mm256_blend_epi32::<IMM>(a, b)
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn chi(a: __m256i, b: __m256i, c: __m256i) -> __m256i {
+    xor(a, _mm256_andnot_si256(b, c))
+}
+
+#[cfg(feature = "parallel")]
+#[target_feature(enable = "avx2")]
+unsafe fn theta(s: &[__m256i; PLEN]) -> [__m256i; 5] {
+    let c0 = xor(xor(s[0], s[5]), xor(xor(s[10], s[15]), s[20]));
+    let c1 = xor(xor(s[1], s[6]), xor(xor(s[11], s[16]), s[21]));
+    let c2 = xor(xor(s[2], s[7]), xor(xor(s[12], s[17]), s[22]));
+    let c3 = xor(xor(s[3], s[8]), xor(xor(s[13], s[18]), s[23]));
+    let c4 = xor(xor(s[4], s[9]), xor(xor(s[14], s[19]), s[24]));
+
+    [
+        xor(c4, rol::<1, 63>(c1)),
+        xor(c0, rol::<1, 63>(c2)),
+        xor(c1, rol::<1, 63>(c3)),
+        xor(c2, rol::<1, 63>(c4)),
+        xor(c3, rol::<1, 63>(c0)),
+    ]
+}
+
+#[cfg(feature = "parallel")]
+#[target_feature(enable = "avx2")]
+unsafe fn rho_pi(s: &[__m256i; PLEN], d: &[__m256i; 5]) -> [__m256i; PLEN] {
+    [
+        xor(s[0], d[0]),
+        rol::<44, 20>(xor(s[6], d[1])),
+        rol::<43, 21>(xor(s[12], d[2])),
+        rol::<21, 43>(xor(s[18], d[3])),
+        rol::<14, 50>(xor(s[24], d[4])),
+        rol::<28, 36>(xor(s[3], d[3])),
+        rol::<20, 44>(xor(s[9], d[4])),
+        rol::<3, 61>(xor(s[10], d[0])),
+        rol::<45, 19>(xor(s[16], d[1])),
+        rol::<61, 3>(xor(s[22], d[2])),
+        rol::<1, 63>(xor(s[1], d[1])),
+        rol::<6, 58>(xor(s[7], d[2])),
+        rol::<25, 39>(xor(s[13], d[3])),
+        rol::<8, 56>(xor(s[19], d[4])),
+        rol::<18, 46>(xor(s[20], d[0])),
+        rol::<27, 37>(xor(s[4], d[4])),
+        rol::<36, 28>(xor(s[5], d[0])),
+        rol::<10, 54>(xor(s[11], d[1])),
+        rol::<15, 49>(xor(s[17], d[2])),
+        rol::<56, 8>(xor(s[23], d[3])),
+        rol::<62, 2>(xor(s[2], d[2])),
+        rol::<55, 9>(xor(s[8], d[3])),
+        rol::<39, 25>(xor(s[14], d[4])),
+        rol::<41, 23>(xor(s[15], d[0])),
+        rol::<2, 62>(xor(s[21], d[1])),
+    ]
+}
+
+#[cfg(feature = "parallel")]
+#[target_feature(enable = "avx2")]
+unsafe fn chi_iota(b: &[__m256i; PLEN], rc: u64) -> [__m256i; PLEN] {
+    let mut s = [_mm256_setzero_si256(); PLEN];
+    for y in (0..PLEN).step_by(5) {
+        for x in 0..5 {
+            let t = _mm256_andnot_si256(b[y + (x + 1) % 5], b[y + (x + 2) % 5]);
+            s[y + x] = xor(b[y + x], t);
+        }
+    }
+    let rc = [rc; 4];
+    // SAFETY: `rc` is 32 bytes long
+    s[0] = xor(s[0], unsafe { _mm256_loadu_si256(rc.as_ptr().cast()) });
+    s
+}
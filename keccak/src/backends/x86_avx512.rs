@@ -0,0 +1,301 @@
+//! x86 AVX-512 intrinsics-based backend.
+
+use crate::consts::{PLEN, rc1600};
+use crate::types::Fn1600;
+#[cfg(feature = "parallel")]
+use crate::types::ParFn1600;
+
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+#[cfg(feature = "parallel")]
+use hybrid_array::{Array, typenum::U8};
+
+/// x86 backend implemented using the AVX-512F and AVX-512VL extensions.
+pub(crate) struct Backend;
+
+impl super::Backend for Backend {
+    #[cfg(feature = "parallel")]
+    impl_soft_small_par!();
+    #[cfg(feature = "parallel")]
+    type ParSize1600 = U8;
+
+    #[inline]
+    fn get_p1600<const ROUNDS: usize>() -> Fn1600 {
+        // SAFETY: the backend is used only after required target feature checks
+        |state| unsafe { p1600_avx512(state, rc1600::<ROUNDS>()) }
+    }
+
+    #[inline]
+    fn fast_loop_absorb_p1600<const ROUNDS: usize>(
+        state: &mut [u64; PLEN],
+        lane_count: usize,
+        data: &[u8],
+    ) -> usize {
+        // SAFETY: the backend is used only after required target feature checks
+        unsafe { fast_loop_absorb_avx512(state, lane_count, data, rc1600::<ROUNDS>()) }
+    }
+
+    #[cfg(feature = "parallel")]
+    #[inline]
+    fn get_par_p1600<const ROUNDS: usize>() -> ParFn1600<Self> {
+        // SAFETY: the backend is used only after required target feature checks
+        |Array(state)| unsafe { p1600_avx512_times8(state, rc1600::<ROUNDS>()) }
+    }
+}
+
+/// Build a vector of 64-bit lanes. Lanes after the listed ones are filled with `5, 6, 7`,
+/// so index vectors keep the unused lanes of rows in place.
+macro_rules! lanes {
+    ($a:expr, $b:expr, $c:expr, $d:expr, $e:expr) => {
+        _mm512_setr_epi64($a, $b, $c, $d, $e, 5, 6, 7)
+    };
+}
+
+/// Mask of the 5 lanes of a row.
+const ROW_MASK: __mmask8 = 0b0001_1111;
+
+/// Keccak-p1600 round loop over a state stored as 5 rows in the lower lanes of ZMM registers.
+///
+/// Similarly to XKCP's `KeccakP-1600-AVX512`, theta and pi move lanes with `vpermq`,
+/// rho is a single `vprolvq` per row, and XOR and chi use `vpternlogq`. After the pi
+/// permutation every register holds one column of the new state, so chi is computed
+/// without lane moves and the state is transposed back into rows at the end of the round.
+#[target_feature(enable = "avx512f,avx512vl")]
+fn rounds_x1(a: &mut [__m512i; 5], round_consts: &[u64]) {
+    let prev = lanes!(4, 0, 1, 2, 3);
+    let next = lanes!(1, 2, 3, 4, 0);
+    let rho = [
+        lanes!(0, 1, 62, 28, 27),
+        lanes!(36, 44, 6, 55, 20),
+        lanes!(3, 10, 43, 25, 39),
+        lanes!(41, 45, 15, 21, 8),
+        lanes!(18, 2, 61, 56, 14),
+    ];
+    // Lane `y` of the row `x` is moved to the row `y` of the new state by pi
+    let pi = [
+        lanes!(0, 3, 1, 4, 2),
+        lanes!(1, 4, 2, 0, 3),
+        lanes!(2, 0, 3, 1, 4),
+        lanes!(3, 1, 4, 2, 0),
+        lanes!(4, 2, 0, 3, 1),
+    ];
+    let lo = _mm512_setr_epi64(0, 1, 8, 9, 4, 5, 6, 7);
+    let mid = _mm512_setr_epi64(2, 3, 10, 11, 4, 5, 6, 7);
+    let hi = _mm512_setr_epi64(4, 5, 12, 13, 4, 5, 6, 7);
+
+    for &rc in round_consts {
+        // Theta
+        let c = _mm512_ternarylogic_epi64::<0x96>(
+            _mm512_ternarylogic_epi64::<0x96>(a[0], a[1], a[2]),
+            a[3],
+            a[4],
+        );
+        let c_prev = _mm512_permutexvar_epi64(prev, c);
+        let c_next = _mm512_rol_epi64::<1>(_mm512_permutexvar_epi64(next, c));
+        // Theta, rho, and the lane moves of pi
+        let b: [__m512i; 5] = core::array::from_fn(|x| {
+            let t = _mm512_ternarylogic_epi64::<0x96>(a[x], c_prev, c_next);
+            _mm512_permutexvar_epi64(pi[x], _mm512_rolv_epi64(t, rho[x]))
+        });
+
+        // Chi over columns
+        let mut t: [__m512i; 5] = core::array::from_fn(|x| {
+            _mm512_ternarylogic_epi64::<0xD2>(b[x], b[(x + 1) % 5], b[(x + 2) % 5])
+        });
+
+        // Iota
+        #[allow(clippy::cast_possible_wrap)]
+        let rc = _mm512_maskz_set1_epi64(1, rc as i64);
+        t[0] = _mm512_xor_si512(t[0], rc);
+
+        // Transpose columns back into rows
+        let t01l = _mm512_unpacklo_epi64(t[0], t[1]);
+        let t01h = _mm512_unpackhi_epi64(t[0], t[1]);
+        let t23l = _mm512_unpacklo_epi64(t[2], t[3]);
+        let t23h = _mm512_unpackhi_epi64(t[2], t[3]);
+        let rows = [
+            (t01l, lo, t23l),
+            (t01h, lo, t23h),
+            (t01l, mid, t23l),
+            (t01h, mid, t23h),
+            (t01l, hi, t23l),
+        ];
+        for (y, (v, (u, idx, w))) in a.iter_mut().zip(rows).enumerate() {
+            #[allow(clippy::cast_possible_wrap, clippy::cast_possible_truncation)]
+            let col4 = _mm512_set1_epi64(y as i64);
+            let row = _mm512_permutex2var_epi64(u, idx, w);
+            *v = _mm512_mask_permutexvar_epi64(row, 0b1_0000, col4, t[4]);
+        }
+    }
+}
+
+#[cfg(feature = "parallel")]
+#[target_feature(enable = "avx512f,avx512vl")]
+fn rounds_x8(s: &mut [__m512i; PLEN], round_consts: &[u64]) {
+    for &rc in round_consts {
+        // Theta
+        let c: [__m512i; 5] = core::array::from_fn(|x| {
+            _mm512_ternarylogic_epi64::<0x96>(
+                s[x],
+                s[x + 5],
+                _mm512_ternarylogic_epi64::<0x96>(s[x + 10], s[x + 15], s[x + 20]),
+            )
+        });
+        let r: [__m512i; 5] = core::array::from_fn(|x| _mm512_rol_epi64::<1>(c[x]));
+        let [c0, c1, c2, c3, c4] = c;
+        let [r0, r1, r2, r3, r4] = r;
+        let t = _mm512_ternarylogic_epi64::<0x96>;
+
+        // Theta (lane update), rho and pi
+        let b = [
+            t(s[0], c4, r1),
+            _mm512_rol_epi64::<44>(t(s[6], c0, r2)),
+            _mm512_rol_epi64::<43>(t(s[12], c1, r3)),
+            _mm512_rol_epi64::<21>(t(s[18], c2, r4)),
+            _mm512_rol_epi64::<14>(t(s[24], c3, r0)),
+            _mm512_rol_epi64::<28>(t(s[3], c2, r4)),
+            _mm512_rol_epi64::<20>(t(s[9], c3, r0)),
+            _mm512_rol_epi64::<3>(t(s[10], c4, r1)),
+            _mm512_rol_epi64::<45>(t(s[16], c0, r2)),
+            _mm512_rol_epi64::<61>(t(s[22], c1, r3)),
+            _mm512_rol_epi64::<1>(t(s[1], c0, r2)),
+            _mm512_rol_epi64::<6>(t(s[7], c1, r3)),
+            _mm512_rol_epi64::<25>(t(s[13], c2, r4)),
+            _mm512_rol_epi64::<8>(t(s[19], c3, r0)),
+            _mm512_rol_epi64::<18>(t(s[20], c4, r1)),
+            _mm512_rol_epi64::<27>(t(s[4], c3, r0)),
+            _mm512_rol_epi64::<36>(t(s[5], c4, r1)),
+            _mm512_rol_epi64::<10>(t(s[11], c0, r2)),
+            _mm512_rol_epi64::<15>(t(s[17], c1, r3)),
+            _mm512_rol_epi64::<56>(t(s[23], c2, r4)),
+            _mm512_rol_epi64::<62>(t(s[2], c1, r3)),
+            _mm512_rol_epi64::<55>(t(s[8], c2, r4)),
+            _mm512_rol_epi64::<39>(t(s[14], c3, r0)),
+            _mm512_rol_epi64::<41>(t(s[15], c4, r1)),
+            _mm512_rol_epi64::<2>(t(s[21], c0, r2)),
+        ];
+
+        // Chi
+        for y in (0..PLEN).step_by(5) {
+            for x in 0..5 {
+                s[y + x] = _mm512_ternarylogic_epi64::<0xD2>(
+                    b[y + x],
+                    b[y + (x + 1) % 5],
+                    b[y + (x + 2) % 5],
+                );
+            }
+        }
+
+        // Iota
+        #[allow(clippy::cast_possible_wrap)]
+        let rc = _mm512_set1_epi64(rc as i64);
+        s[0] = _mm512_xor_si512(s[0], rc);
+    }
+}
+
+/// Keccak-p1600 with AVX-512.
+///
+/// Every row of the state is kept in the lower 5 lanes of its own ZMM register.
+#[target_feature(enable = "avx512f,avx512vl")]
+unsafe fn p1600_avx512(state: &mut [u64; PLEN], round_consts: &[u64]) {
+    // SAFETY: the caller guarantees availability of the required target features
+    let mut a = unsafe { load_rows(state) };
+    rounds_x1(&mut a, round_consts);
+    // SAFETY: the caller guarantees availability of the required target features
+    unsafe { store_rows(state, a) };
+}
+
+/// Absorb full blocks of `data` into `state` with AVX-512 keeping the state
+/// in registers between blocks.
+#[target_feature(enable = "avx512f,avx512vl")]
+unsafe fn fast_loop_absorb_avx512(
+    state: &mut [u64; PLEN],
+    lane_count: usize,
+    data: &[u8],
+    round_consts: &[u64],
+) -> usize {
+    assert!(
+        (1..=PLEN).contains(&lane_count),
+        "invalid lane count: {lane_count}",
+    );
+
+    // Masks of the block lanes in every row
+    #[allow(clippy::cast_possible_truncation)]
+    let masks: [__mmask8; 5] =
+        core::array::from_fn(|y| ROW_MASK >> (5 - lane_count.saturating_sub(5 * y).min(5)));
+
+    // SAFETY: the caller guarantees availability of the required target features
+    let mut a = unsafe { load_rows(state) };
+
+    let mut blocks = data.chunks_exact(8 * lane_count);
+    for block in &mut blocks {
+        let ptr: *const i64 = block.as_ptr().cast();
+        for (y, (v, mask)) in a.iter_mut().zip(masks).enumerate() {
+            // SAFETY: the mask selects only the lanes inside of `block`
+            let t = unsafe { _mm512_maskz_loadu_epi64(mask, ptr.add(5 * y)) };
+            *v = _mm512_xor_si512(*v, t);
+        }
+        rounds_x1(&mut a, round_consts);
+    }
+
+    // SAFETY: the caller guarantees availability of the required target features
+    unsafe { store_rows(state, a) };
+
+    data.len() - blocks.remainder().len()
+}
+
+/// Load every row of `state` into the lower lanes of its own ZMM register.
+#[inline]
+#[target_feature(enable = "avx512f,avx512vl")]
+unsafe fn load_rows(state: &[u64; PLEN]) -> [__m512i; 5] {
+    let ptr: *const i64 = state.as_ptr().cast();
+    // SAFETY: the mask selects only the lanes inside of `state`
+    core::array::from_fn(|y| unsafe { _mm512_maskz_loadu_epi64(ROW_MASK, ptr.add(5 * y)) })
+}
+
+/// Store lower lanes of the ZMM registers into rows of `state`.
+#[inline]
+#[target_feature(enable = "avx512f,avx512vl")]
+unsafe fn store_rows(state: &mut [u64; PLEN], a: [__m512i; 5]) {
+    let ptr: *mut i64 = state.as_mut_ptr().cast();
+    for (y, v) in a.into_iter().enumerate() {
+        // SAFETY: the mask selects only the lanes inside of `state`
+        unsafe { _mm512_mask_storeu_epi64(ptr.add(5 * y), ROW_MASK, v) };
+    }
+}
+
+/// Keccak-p1600 with AVX-512 with support for 8 parallel states.
+///
+/// Every 512-bit register holds the same lane of all 8 states.
+#[cfg(feature = "parallel")]
+#[target_feature(enable = "avx512f,avx512vl")]
+unsafe fn p1600_avx512_times8(state: &mut [[u64; PLEN]; 8], round_consts: &[u64]) {
+    #[allow(clippy::cast_possible_wrap)]
+    const STRIDE: i64 = PLEN as i64;
+    let idx = _mm512_setr_epi64(
+        0,
+        STRIDE,
+        2 * STRIDE,
+        3 * STRIDE,
+        4 * STRIDE,
+        5 * STRIDE,
+        6 * STRIDE,
+        7 * STRIDE,
+    );
+
+    let base: *mut u64 = state.as_mut_ptr().cast();
+    let mut s = [_mm512_setzero_si512(); PLEN];
+    for (i, v) in s.iter_mut().enumerate() {
+        // SAFETY: all gathered lanes lie inside of `state`
+        *v = unsafe { _mm512_i64gather_epi64::<8>(idx, base.add(i).cast_const().cast()) };
+    }
+
+    rounds_x8(&mut s, round_consts);
+
+    for (i, v) in s.into_iter().enumerate() {
+        // SAFETY: all scattered lanes lie inside of `state`
+        unsafe { _mm512_i64scatter_epi64::<8>(base.add(i).cast(), idx, v) };
+    }
+}
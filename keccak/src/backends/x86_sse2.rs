@@ -0,0 +1,169 @@
+//! x86 SSE2 intrinsics-based backend.
+
+// TODO: remove when MSRV 1.87
+#![allow(unsafe_op_in_unsafe_fn)]
+
+use crate::types::Fn1600;
+#[cfg(feature = "parallel")]
+use crate::{
+    consts::{PLEN, rc1600},
+    types::ParFn1600,
+};
+
+#[cfg(all(feature = "parallel", target_arch = "x86"))]
+use core::arch::x86::*;
+#[cfg(all(feature = "parallel", target_arch = "x86_64"))]
+use core::arch::x86_64::*;
+#[cfg(feature = "parallel")]
+use hybrid_array::{Array, typenum::U2};
+
+/// x86 backend implemented using the SSE2 extension.
+///
+/// Scalar code outperforms SSE2 for a single state, so only the parallel function
+/// is implemented using SIMD.
+pub(crate) struct Backend;
+
+impl super::Backend for Backend {
+    #[cfg(feature = "parallel")]
+    impl_soft_small_par!();
+    #[cfg(feature = "parallel")]
+    type ParSize1600 = U2;
+
+    #[inline]
+    fn get_p1600<const ROUNDS: usize>() -> Fn1600 {
+        <super::ScalarBackend as super::Backend>::get_p1600::<ROUNDS>()
+    }
+
+    #[cfg(feature = "parallel")]
+    #[inline]
+    fn get_par_p1600<const ROUNDS: usize>() -> ParFn1600<Self> {
+        // SAFETY: the backend is used only after required target feature checks
+        |Array(state)| unsafe { p1600_sse2_times2(state, rc1600::<ROUNDS>()) }
+    }
+}
+
+/// Keccak-p1600 with SSE2 with support for 2 parallel states.
+///
+/// Every 128-bit register holds the same lane of both states.
+#[cfg(feature = "parallel")]
+#[target_feature(enable = "sse2")]
+unsafe fn p1600_sse2_times2(state: &mut [[u64; PLEN]; 2], round_consts: &[u64]) {
+    let [s0, s1] = state;
+    let mut s = [_mm_setzero_si128(); PLEN];
+    for i in (0..PLEN - 1).step_by(2) {
+        // SAFETY: `s0[i..]` and `s1[i..]` are at least 16 bytes long
+        let (r0, r1) = unsafe {
+            (
+                _mm_loadu_si128(s0[i..].as_ptr().cast()),
+                _mm_loadu_si128(s1[i..].as_ptr().cast()),
+            )
+        };
+        s[i] = _mm_unpacklo_epi64(r0, r1);
+        s[i + 1] = _mm_unpackhi_epi64(r0, r1);
+    }
+    let last = [s0[24], s1[24]];
+    // SAFETY: `last` is 16 bytes long
+    s[24] = unsafe { _mm_loadu_si128(last.as_ptr().cast()) };
+
+    for &rc in round_consts {
+        let d = theta(&s);
+        let b = rho_pi(&s, &d);
+        s = chi_iota(&b, rc);
+    }
+
+    for i in (0..PLEN - 1).step_by(2) {
+        let r0 = _mm_unpacklo_epi64(s[i], s[i + 1]);
+        let r1 = _mm_unpackhi_epi64(s[i], s[i + 1]);
+        // SAFETY: `s0[i..]` and `s1[i..]` are at least 16 bytes long
+        unsafe {
+            _mm_storeu_si128(s0[i..].as_mut_ptr().cast(), r0);
+            _mm_storeu_si128(s1[i..].as_mut_ptr().cast(), r1);
+        }
+    }
+    let mut last = [0u64; 2];
+    // SAFETY: `last` is 16 bytes long
+    unsafe { _mm_storeu_si128(last.as_mut_ptr().cast(), s[24]) };
+    [s0[24], s1[24]] = last;
+}
+
+#[cfg(feature = "parallel")]
+#[inline]
+#[target_feature(enable = "sse2")]
+unsafe fn xor(a: __m128i, b: __m128i) -> __m128i {
+    _mm_xor_si128(a, b)
+}
+
+/// Rotate every 64-bit lane left by `L` bits. `R` must be equal to `64 - L`.
+#[cfg(feature = "parallel")]
+#[inline]
+#[target_feature(enable = "sse2")]
+unsafe fn rol<const L: i32, const R: i32>(a: __m128i) -> __m128i {
+    const { assert!(L + R == 64) };
+    _mm_or_si128(_mm_slli_epi64::<L>(a), _mm_srli_epi64::<R>(a))
+}
+
+#[cfg(feature = "parallel")]
+#[target_feature(enable = "sse2")]
+unsafe fn theta(s: &[__m128i; PLEN]) -> [__m128i; 5] {
+    let c0 = xor(xor(s[0], s[5]), xor(xor(s[10], s[15]), s[20]));
+    let c1 = xor(xor(s[1], s[6]), xor(xor(s[11], s[16]), s[21]));
+    let c2 = xor(xor(s[2], s[7]), xor(xor(s[12], s[17]), s[22]));
+    let c3 = xor(xor(s[3], s[8]), xor(xor(s[13], s[18]), s[23]));
+    let c4 = xor(xor(s[4], s[9]), xor(xor(s[14], s[19]), s[24]));
+
+    [
+        xor(c4, rol::<1, 63>(c1)),
+        xor(c0, rol::<1, 63>(c2)),
+        xor(c1, rol::<1, 63>(c3)),
+        xor(c2, rol::<1, 63>(c4)),
+        xor(c3, rol::<1, 63>(c0)),
+    ]
+}
+
+#[cfg(feature = "parallel")]
+#[target_feature(enable = "sse2")]
+unsafe fn rho_pi(s: &[__m128i; PLEN], d: &[__m128i; 5]) -> [__m128i; PLEN] {
+    [
+        xor(s[0], d[0]),
+        rol::<44, 20>(xor(s[6], d[1])),
+        rol::<43, 21>(xor(s[12], d[2])),
+        rol::<21, 43>(xor(s[18], d[3])),
+        rol::<14, 50>(xor(s[24], d[4])),
+        rol::<28, 36>(xor(s[3], d[3])),
+        rol::<20, 44>(xor(s[9], d[4])),
+        rol::<3, 61>(xor(s[10], d[0])),
+        rol::<45, 19>(xor(s[16], d[1])),
+        rol::<61, 3>(xor(s[22], d[2])),
+        rol::<1, 63>(xor(s[1], d[1])),
+        rol::<6, 58>(xor(s[7], d[2])),
+        rol::<25, 39>(xor(s[13], d[3])),
+        rol::<8, 56>(xor(s[19], d[4])),
+        rol::<18, 46>(xor(s[20], d[0])),
+        rol::<27, 37>(xor(s[4], d[4])),
+        rol::<36, 28>(xor(s[5], d[0])),
+        rol::<10, 54>(xor(s[11], d[1])),
+        rol::<15, 49>(xor(s[17], d[2])),
+        rol::<56, 8>(xor(s[23], d[3])),
+        rol::<62, 2>(xor(s[2], d[2])),
+        rol::<55, 9>(xor(s[8], d[3])),
+        rol::<39, 25>(xor(s[14], d[4])),
+        rol::<41, 23>(xor(s[15], d[0])),
+        rol::<2, 62>(xor(s[21], d[1])),
+    ]
+}
+
+#[cfg(feature = "parallel")]
+#[target_feature(enable = "sse2")]
+unsafe fn chi_iota(b: &[__m128i; PLEN], rc: u64) -> [__m128i; PLEN] {
+    let mut s = [_mm_setzero_si128(); PLEN];
+    for y in (0..PLEN).step_by(5) {
+        for x in 0..5 {
+            let t = _mm_andnot_si128(b[y + (x + 1) % 5], b[y + (x + 2) % 5]);
+            s[y + x] = xor(b[y + x], t);
+        }
+    }
+    #[allow(clippy::cast_possible_wrap)]
+    let rc = _mm_set1_epi64x(rc as i64);
+    s[0] = xor(s[0], rc);
+    s
+}
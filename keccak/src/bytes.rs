@@ -0,0 +1,145 @@
+//! Byte-oriented access to Keccak states.
+//!
+//! The functions follow the state-and-permutation (SnP) interface of [XKCP]:
+//! bytes of a state are numbered in the little-endian order of its lanes,
+//! independently of the host endianness.
+//!
+//! [XKCP]: https://github.com/XKCP/XKCP/blob/master/lib/low/common/SnP-Relaned.h
+use crate::consts::PLEN;
+use crate::types::{State200, State400, State800, State1600};
+use core::ops::Range;
+
+/// Byte-oriented state-and-permutation interface implemented for Keccak states.
+pub trait StateBytes {
+    /// Size of the state in bytes.
+    const BYTE_LEN: usize;
+
+    /// XOR `data` into the state starting at the byte `offset`.
+    ///
+    /// # Panics
+    /// If `offset + data.len()` is bigger than [`StateBytes::BYTE_LEN`].
+    fn add_bytes(&mut self, data: &[u8], offset: usize);
+
+    /// Overwrite state bytes starting at the byte `offset` with `data`.
+    ///
+    /// # Panics
+    /// If `offset + data.len()` is bigger than [`StateBytes::BYTE_LEN`].
+    fn overwrite_bytes(&mut self, data: &[u8], offset: usize);
+
+    /// Overwrite the first `byte_count` bytes of the state with zeroes.
+    ///
+    /// # Panics
+    /// If `byte_count` is bigger than [`StateBytes::BYTE_LEN`].
+    fn overwrite_with_zeroes(&mut self, byte_count: usize);
+
+    /// Copy state bytes starting at the byte `offset` into `data`.
+    ///
+    /// # Panics
+    /// If `offset + data.len()` is bigger than [`StateBytes::BYTE_LEN`].
+    fn extract_bytes(&self, data: &mut [u8], offset: usize);
+
+    /// XOR state bytes starting at the byte `offset` with `input` and write
+    /// the result into `output`.
+    ///
+    /// # Panics
+    /// If `input` and `output` have different lengths or if `offset + input.len()`
+    /// is bigger than [`StateBytes::BYTE_LEN`].
+    fn extract_and_add_bytes(&self, input: &[u8], output: &mut [u8], offset: usize);
+}
+
+/// Split the byte range `offset..offset + len` of a state into per-lane parts.
+///
+/// Yields lane index, byte range inside of the lane, and the corresponding range
+/// inside of the processed data.
+#[inline(always)]
+fn lane_chunks<const LANE_LEN: usize>(
+    offset: usize,
+    len: usize,
+) -> impl Iterator<Item = (usize, Range<usize>, Range<usize>)> {
+    let end = offset.checked_add(len);
+    assert!(
+        end.is_some_and(|end| end <= PLEN * LANE_LEN),
+        "byte range is out of state bounds",
+    );
+
+    let mut pos = 0;
+    core::iter::from_fn(move || {
+        if pos == len {
+            return None;
+        }
+        let lane = (offset + pos) / LANE_LEN;
+        let start = (offset + pos) % LANE_LEN;
+        let n = (LANE_LEN - start).min(len - pos);
+        let item = (lane, start..start + n, pos..pos + n);
+        pos += n;
+        Some(item)
+    })
+}
+
+macro_rules! impl_state_bytes {
+    ($state:ty, $lane:ty) => {
+        impl StateBytes for $state {
+            const BYTE_LEN: usize = PLEN * size_of::<$lane>();
+
+            #[inline]
+            fn add_bytes(&mut self, data: &[u8], offset: usize) {
+                const LANE_LEN: usize = size_of::<$lane>();
+                for (i, lane_range, data_range) in lane_chunks::<LANE_LEN>(offset, data.len()) {
+                    let mut buf = [0u8; LANE_LEN];
+                    buf[lane_range].copy_from_slice(&data[data_range]);
+                    self[i] ^= <$lane>::from_le_bytes(buf);
+                }
+            }
+
+            #[inline]
+            fn overwrite_bytes(&mut self, data: &[u8], offset: usize) {
+                const LANE_LEN: usize = size_of::<$lane>();
+                for (i, lane_range, data_range) in lane_chunks::<LANE_LEN>(offset, data.len()) {
+                    let mut buf = self[i].to_le_bytes();
+                    buf[lane_range].copy_from_slice(&data[data_range]);
+                    self[i] = <$lane>::from_le_bytes(buf);
+                }
+            }
+
+            #[inline]
+            fn overwrite_with_zeroes(&mut self, byte_count: usize) {
+                const LANE_LEN: usize = size_of::<$lane>();
+                for (i, lane_range, _) in lane_chunks::<LANE_LEN>(0, byte_count) {
+                    let mut buf = self[i].to_le_bytes();
+                    buf[lane_range].fill(0);
+                    self[i] = <$lane>::from_le_bytes(buf);
+                }
+            }
+
+            #[inline]
+            fn extract_bytes(&self, data: &mut [u8], offset: usize) {
+                const LANE_LEN: usize = size_of::<$lane>();
+                for (i, lane_range, data_range) in lane_chunks::<LANE_LEN>(offset, data.len()) {
+                    data[data_range].copy_from_slice(&self[i].to_le_bytes()[lane_range]);
+                }
+            }
+
+            #[inline]
+            fn extract_and_add_bytes(&self, input: &[u8], output: &mut [u8], offset: usize) {
+                const LANE_LEN: usize = size_of::<$lane>();
+                assert_eq!(
+                    input.len(),
+                    output.len(),
+                    "input and output have different lengths",
+                );
+                for (i, lane_range, data_range) in lane_chunks::<LANE_LEN>(offset, input.len()) {
+                    let lane = self[i].to_le_bytes();
+                    let (src, dst) = (&input[data_range.clone()], &mut output[data_range]);
+                    for ((d, s), l) in dst.iter_mut().zip(src).zip(&lane[lane_range]) {
+                        *d = s ^ l;
+                    }
+                }
+            }
+        }
+    };
+}
+
+impl_state_bytes!(State200, u8);
+impl_state_bytes!(State400, u16);
+impl_state_bytes!(State800, u32);
+impl_state_bytes!(State1600, u64);
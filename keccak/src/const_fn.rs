@@ -0,0 +1,300 @@
+//! Keccak-p permutations and SHA-3 functions usable in const contexts.
+//!
+//! The functions in this module are straightforward implementations intended
+//! for computing constants at compile time, e.g. domain separation values, precomputed
+//! sponge states, or static test tables. At runtime prefer the functions provided
+//! by [`Keccak`][crate::Keccak], which dispatch to the fastest available backend.
+use crate::consts::{
+    F200_ROUNDS, F400_ROUNDS, F800_ROUNDS, F1600_ROUNDS, PI, RHO, round_constants,
+};
+use crate::sp800_185::{bit_len, left_encode};
+use crate::{State200, State400, State800, State1600};
+
+macro_rules! impl_const_p {
+    ($p:ident, $f:ident, $state:ty, $lane:ty, $f_rounds:expr, $width:literal) => {
+        #[doc = concat!("Apply the Keccak-p\\[", $width, "\\] permutation with `ROUNDS` rounds to `state`.")]
+        ///
+        /// If `ROUNDS` is bigger than the number of rounds of the corresponding Keccak-f
+        /// permutation, the Keccak-f rounds are preceded by rounds with negative indices.
+        #[allow(clippy::cast_possible_truncation, trivial_numeric_casts)]
+        pub const fn $p<const ROUNDS: usize>(state: &mut $state) {
+            let round_consts = const { round_constants::<ROUNDS>($f_rounds) };
+            let mut round = 0;
+            while round < ROUNDS {
+                // Theta
+                let mut c = [0; 5];
+                let mut x = 0;
+                while x < 5 {
+                    c[x] = state[x] ^ state[x + 5] ^ state[x + 10] ^ state[x + 15] ^ state[x + 20];
+                    x += 1;
+                }
+                let mut x = 0;
+                while x < 5 {
+                    let d = c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+                    let mut y = 0;
+                    while y < 25 {
+                        state[y + x] ^= d;
+                        y += 5;
+                    }
+                    x += 1;
+                }
+
+                // Rho and pi
+                let mut last = state[1];
+                let mut i = 0;
+                while i < 24 {
+                    let t = state[PI[i]];
+                    state[PI[i]] = last.rotate_left(RHO[i]);
+                    last = t;
+                    i += 1;
+                }
+
+                // Chi
+                let mut y = 0;
+                while y < 25 {
+                    let a = [state[y], state[y + 1], state[y + 2], state[y + 3], state[y + 4]];
+                    let mut x = 0;
+                    while x < 5 {
+                        state[y + x] = a[x] ^ (!a[(x + 1) % 5] & a[(x + 2) % 5]);
+                        x += 1;
+                    }
+                    y += 5;
+                }
+
+                // Iota
+                state[0] ^= round_consts[round] as $lane;
+                round += 1;
+            }
+        }
+
+        #[doc = concat!("Apply the Keccak-f\\[", $width, "\\] permutation to `state`.")]
+        pub const fn $f(state: &mut $state) {
+            $p::<$f_rounds>(state);
+        }
+    };
+}
+
+impl_const_p!(p200, f200, State200, u8, F200_ROUNDS, "200");
+impl_const_p!(p400, f400, State400, u16, F400_ROUNDS, "400");
+impl_const_p!(p800, f800, State800, u32, F800_ROUNDS, "800");
+impl_const_p!(p1600, f1600, State1600, u64, F1600_ROUNDS, "1600");
+
+/// Keccak sponge over [`State1600`] usable in const contexts.
+///
+/// This is a const counterpart of [`Sponge`][crate::sponge::Sponge] with the same
+/// padding rules. The current state can be retrieved with [`ConstSponge::state`].
+///
+/// # Panics
+/// Construction fails at compile time if `RATE` is zero or if it is not smaller than 200.
+#[derive(Clone, Debug)]
+pub struct ConstSponge<const RATE: usize, const ROUNDS: usize> {
+    state: State1600,
+    pos: usize,
+    squeezing: bool,
+}
+
+impl<const RATE: usize, const ROUNDS: usize> ConstSponge<RATE, ROUNDS> {
+    /// Create new sponge with zero initial state.
+    #[must_use]
+    pub const fn new() -> Self {
+        const {
+            assert!(
+                RATE > 0 && RATE < 200,
+                "rate must be non-zero and smaller than the state size",
+            );
+        }
+        Self {
+            state: [0; 25],
+            pos: 0,
+            squeezing: false,
+        }
+    }
+
+    /// Get the current state of the sponge.
+    #[must_use]
+    pub const fn state(&self) -> &State1600 {
+        &self.state
+    }
+
+    /// XOR `byte` into the state at the byte `offset`.
+    const fn add_byte(&mut self, byte: u8, offset: usize) {
+        let mut lane = self.state[offset / 8].to_le_bytes();
+        lane[offset % 8] ^= byte;
+        self.state[offset / 8] = u64::from_le_bytes(lane);
+    }
+
+    /// Absorb `data` into the sponge.
+    ///
+    /// # Panics
+    /// If the sponge is already in the squeezing phase.
+    pub const fn absorb(&mut self, data: &[u8]) {
+        assert!(!self.squeezing, "absorb called in the squeezing phase");
+
+        let mut i = 0;
+        while i < data.len() {
+            self.add_byte(data[i], self.pos);
+            self.pos += 1;
+            if self.pos == RATE {
+                p1600::<ROUNDS>(&mut self.state);
+                self.pos = 0;
+            }
+            i += 1;
+        }
+    }
+
+    /// Absorb the delimited `suffix`, apply `pad10*1` padding, and switch
+    /// to the squeezing phase.
+    ///
+    /// # Panics
+    /// If the sponge is already in the squeezing phase or if `suffix` is equal to zero.
+    pub const fn absorb_last(&mut self, suffix: u8) {
+        assert!(!self.squeezing, "absorb_last called in the squeezing phase");
+        assert!(suffix != 0, "delimited suffix must not be zero");
+
+        self.add_byte(suffix, self.pos);
+        if suffix & 0x80 != 0 && self.pos == RATE - 1 {
+            p1600::<ROUNDS>(&mut self.state);
+        }
+        self.add_byte(0x80, RATE - 1);
+        p1600::<ROUNDS>(&mut self.state);
+
+        self.pos = 0;
+        self.squeezing = true;
+    }
+
+    /// Squeeze output bytes from the sponge.
+    ///
+    /// If the sponge is in the absorbing phase, the padding is applied first
+    /// with the suffix `0x01` (i.e. without domain separation bits).
+    pub const fn squeeze(&mut self, mut out: &mut [u8]) {
+        if !self.squeezing {
+            self.absorb_last(0x01);
+        }
+
+        while !out.is_empty() {
+            if self.pos == RATE {
+                p1600::<ROUNDS>(&mut self.state);
+                self.pos = 0;
+            }
+            let lane = self.state[self.pos / 8].to_le_bytes();
+            let offset = self.pos % 8;
+            let n = min(min(8 - offset, RATE - self.pos), out.len());
+            let (head, tail) = out.split_at_mut(n);
+            let mut i = 0;
+            while i < n {
+                head[i] = lane[offset + i];
+                i += 1;
+            }
+            out = tail;
+            self.pos += n;
+        }
+    }
+
+    /// Absorb `bytepad(encode_string(s_1) || ... || encode_string(s_n), RATE)`, where `s_i`
+    /// are the elements of `strings`.
+    ///
+    /// See [`sp800_185::bytepad`][crate::sp800_185::bytepad] for the runtime counterpart.
+    ///
+    /// # Panics
+    /// If the sponge is already in the squeezing phase.
+    #[allow(clippy::cast_possible_truncation)]
+    pub const fn absorb_bytepad(&mut self, strings: &[&[u8]]) {
+        let prefix = left_encode(RATE as u64);
+        self.absorb(prefix.as_bytes());
+        let mut len = prefix.as_bytes().len();
+
+        let mut i = 0;
+        while i < strings.len() {
+            let s = strings[i];
+            let s_len = left_encode(bit_len(s.len()));
+            self.absorb(s_len.as_bytes());
+            self.absorb(s);
+            len += s_len.as_bytes().len() + s.len();
+            i += 1;
+        }
+
+        let pad_len = (RATE - len % RATE) % RATE;
+        self.absorb([0u8; 200].split_at(pad_len).0);
+    }
+}
+
+/// Minimum of `a` and `b` usable in const contexts.
+const fn min(a: usize, b: usize) -> usize {
+    if a < b { a } else { b }
+}
+
+impl<const RATE: usize, const ROUNDS: usize> Default for ConstSponge<RATE, ROUNDS> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Compute the sponge output of `data` with the delimited `suffix`.
+const fn hash<const RATE: usize, const N: usize>(data: &[u8], suffix: u8) -> [u8; N] {
+    let mut sponge = ConstSponge::<RATE, F1600_ROUNDS>::new();
+    sponge.absorb(data);
+    sponge.absorb_last(suffix);
+    let mut out = [0u8; N];
+    sponge.squeeze(&mut out);
+    out
+}
+
+macro_rules! impl_const_sha3 {
+    ($fn_name:ident, $rate:expr, $out_len:expr, $alg:literal) => {
+        #[doc = concat!("Compute ", $alg, " hash of `data`.")]
+        #[must_use]
+        pub const fn $fn_name(data: &[u8]) -> [u8; $out_len] {
+            hash::<$rate, $out_len>(data, 0x06)
+        }
+    };
+}
+
+impl_const_sha3!(sha3_224, 144, 28, "SHA3-224");
+impl_const_sha3!(sha3_256, 136, 32, "SHA3-256");
+impl_const_sha3!(sha3_384, 104, 48, "SHA3-384");
+impl_const_sha3!(sha3_512, 72, 64, "SHA3-512");
+
+/// Compute `N` bytes of SHAKE128 output for `data`.
+#[must_use]
+pub const fn shake128<const N: usize>(data: &[u8]) -> [u8; N] {
+    hash::<168, N>(data, 0x1F)
+}
+
+/// Compute `N` bytes of SHAKE256 output for `data`.
+#[must_use]
+pub const fn shake256<const N: usize>(data: &[u8]) -> [u8; N] {
+    hash::<136, N>(data, 0x1F)
+}
+
+/// Create cSHAKE sponge with rate of `RATE` bytes initialized with the function name
+/// `function_name` and the customization string `customization`.
+///
+/// Use `RATE` equal to 168 for cSHAKE128 and 136 for cSHAKE256. The returned sponge
+/// must be finalized with the cSHAKE suffix `0x04`. Note that cSHAKE with both strings
+/// empty is defined as SHAKE, which absorbs no prefix and uses the suffix `0x1F`.
+#[must_use]
+pub const fn cshake<const RATE: usize>(
+    function_name: &[u8],
+    customization: &[u8],
+) -> ConstSponge<RATE, F1600_ROUNDS> {
+    let mut sponge = ConstSponge::new();
+    sponge.absorb_bytepad(&[function_name, customization]);
+    sponge
+}
+
+/// Create KMAC sponge with rate of `RATE` bytes initialized with the key `key`
+/// and the customization string `customization`.
+///
+/// Use `RATE` equal to 168 for KMAC128 and 136 for KMAC256. Before finalization
+/// with the cSHAKE suffix `0x04` the sponge must absorb the message followed by
+/// `right_encode` of the output length in bits (zero for KMACXOF).
+#[must_use]
+pub const fn kmac<const RATE: usize>(
+    key: &[u8],
+    customization: &[u8],
+) -> ConstSponge<RATE, F1600_ROUNDS> {
+    let mut sponge = cshake::<RATE>(b"KMAC", customization);
+    sponge.absorb_bytepad(&[key]);
+    sponge
+}
@@ -11,6 +11,9 @@ pub const F400_ROUNDS: usize = 20;
 pub const F800_ROUNDS: usize = 22;
 /// Number of rounds used by the `f1600` function.
 pub const F1600_ROUNDS: usize = 24;
+/// Maximum number of Keccak-p\[1600\] rounds supported by the functions which accept
+/// the number of rounds at runtime.
+pub const MAX_RUNTIME_ROUNDS: usize = 32;
 
 pub(crate) const RHO: [u32; 24] = [
     1, 3, 6, 10, 15, 21, 28, 36, 45, 55, 2, 14, 27, 41, 56, 8, 25, 43, 62, 18, 39, 61, 20, 44,
@@ -20,29 +23,63 @@ pub(crate) const PI: [usize; 24] = [
     10, 7, 11, 17, 18, 3, 5, 16, 8, 21, 24, 4, 15, 23, 19, 13, 12, 2, 20, 14, 22, 9, 6, 1,
 ];
 
-pub(crate) const RC: [u64; 24] = [
-    0x0000000000000001,
-    0x0000000000008082,
-    0x800000000000808a,
-    0x8000000080008000,
-    0x000000000000808b,
-    0x0000000080000001,
-    0x8000000080008081,
-    0x8000000000008009,
-    0x000000000000008a,
-    0x0000000000000088,
-    0x0000000080008009,
-    0x000000008000000a,
-    0x000000008000808b,
-    0x800000000000008b,
-    0x8000000000008089,
-    0x8000000000008003,
-    0x8000000000008002,
-    0x8000000000000080,
-    0x000000000000800a,
-    0x800000008000000a,
-    0x8000000080008081,
-    0x8000000000008080,
-    0x0000000080000001,
-    0x8000000080008008,
-];
+/// Compute output bit of the `rc(t)` linear feedback shift register defined in
+/// FIPS 202, Algorithm 5.
+///
+/// The register has period 255, so `t` can be any integer including negative ones.
+#[must_use]
+pub const fn rc(t: i64) -> bool {
+    let mut r: u8 = 1;
+    let mut i = 0;
+    while i < t.rem_euclid(255) {
+        // Shift with the feedback polynomial x^8 + x^6 + x^5 + x^4 + 1
+        r = (r << 1) ^ ((r >> 7) * 0x71);
+        i += 1;
+    }
+    r & 1 == 1
+}
+
+/// Compute the round constant of the round with index `ir` as defined in FIPS 202,
+/// Algorithm 6.
+///
+/// The returned value is the constant for 64-bit lanes. Constants of smaller lanes are
+/// equal to its lower bits. The round indices of Keccak-f\[1600\] are `0..24`, while
+/// Keccak-p with more rounds than Keccak-f starts from negative indices.
+#[must_use]
+pub const fn round_constant(ir: i64) -> u64 {
+    let mut rc_value = 0;
+    let mut j = 0;
+    while j <= 6 {
+        if rc(j + 7 * ir) {
+            rc_value |= 1 << ((1 << j) - 1);
+        }
+        j += 1;
+    }
+    rc_value
+}
+
+/// Compute round constants of Keccak-p with `ROUNDS` rounds over the state whose
+/// Keccak-f permutation has `f_rounds` rounds.
+///
+/// The rounds of Keccak-p match the last rounds of Keccak-f. If `ROUNDS` is bigger
+/// than `f_rounds`, the rounds preceding Keccak-f use negative round indices.
+#[must_use]
+#[allow(clippy::cast_possible_wrap)]
+pub const fn round_constants<const ROUNDS: usize>(f_rounds: usize) -> [u64; ROUNDS] {
+    let first = f_rounds as i64 - ROUNDS as i64;
+    let mut res = [0; ROUNDS];
+    let mut i = 0;
+    while i < ROUNDS {
+        res[i] = round_constant(first + i as i64);
+        i += 1;
+    }
+    res
+}
+
+/// Get round constants of Keccak-p\[1600\] with `ROUNDS` rounds.
+///
+/// The constants are computed at compile time for every used number of rounds.
+#[inline(always)]
+pub(crate) const fn rc1600<const ROUNDS: usize>() -> &'static [u64] {
+    const { &round_constants::<ROUNDS>(F1600_ROUNDS) }
+}
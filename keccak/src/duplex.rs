@@ -0,0 +1,345 @@
+//! Keccak duplex construction and the SpongeWrap authenticated encryption mode.
+//!
+//! See the [Duplexing the sponge] paper for the description of both constructions.
+//!
+//! [Duplexing the sponge]: https://keccak.team/files/SpongeDuplex.pdf
+use crate::Keccak;
+use crate::sponge::{Snp, Width};
+use core::fmt;
+
+/// Frame bit `0` followed by the first bit of `pad10*1` as a delimited suffix.
+const FRAME_0: u8 = 0b10;
+/// Frame bit `1` followed by the first bit of `pad10*1` as a delimited suffix.
+const FRAME_1: u8 = 0b11;
+
+/// Keccak duplex object over the `W` state with rate of `RATE` bytes and the Keccak-p
+/// permutation with `ROUNDS` rounds.
+///
+/// Every duplexing call absorbs one padded input block, applies the permutation,
+/// and returns up to `RATE` bytes of output.
+///
+/// In the outer overwrite mode (see [`Duplex::new_overwrite`]) the padded input block
+/// replaces the outer `RATE` bytes of the state instead of being XORed into it.
+/// [`Duplex::duplexing_overwrite_full`] overwrites the whole state with a padded input
+/// block of `W::BYTE_LEN` bytes.
+#[derive(Clone)]
+pub struct Duplex<W: Width, const RATE: usize, const ROUNDS: usize> {
+    p: fn(&mut W),
+    snp: Snp<W>,
+    state: W,
+    overwrite: bool,
+}
+
+impl<W: Width, const RATE: usize, const ROUNDS: usize> Duplex<W, RATE, ROUNDS> {
+    /// Maximum length of input in bytes accepted by the duplexing functions.
+    pub const MAX_INPUT_LEN: usize = RATE - 1;
+
+    /// Maximum length of input in bytes accepted by [`Duplex::duplexing_overwrite_full`].
+    pub const MAX_FULL_INPUT_LEN: usize = W::BYTE_LEN - 1;
+
+    /// Create new duplex object with zero initial state.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::new_with_keccak(Keccak::new(), false)
+    }
+
+    /// Create new duplex object in the outer overwrite mode with zero initial state.
+    ///
+    /// Only the outer `RATE` bytes of the state are overwritten by the padded input blocks
+    /// of [`Duplex::duplexing`] and [`Duplex::duplexing_with_suffix`].
+    #[must_use]
+    pub fn new_overwrite() -> Self {
+        Self::new_with_keccak(Keccak::new(), true)
+    }
+
+    /// Create new duplex object using the provided [`Keccak`] instance.
+    ///
+    /// If `overwrite` is `true`, the duplex object works in the overwrite mode.
+    #[must_use]
+    pub fn new_with_keccak(keccak: Keccak, overwrite: bool) -> Self {
+        const {
+            assert!(
+                RATE > 1 && RATE < W::BYTE_LEN,
+                "rate must be bigger than one and smaller than the state size",
+            );
+        }
+        Self {
+            p: W::get_p::<ROUNDS>(&keccak),
+            snp: W::get_snp(&keccak),
+            state: W::default(),
+            overwrite,
+        }
+    }
+
+    /// Absorb `input` padded with `pad10*1`, apply the permutation, and write
+    /// the first `out.len()` bytes of the outer part of the state into `out`.
+    ///
+    /// # Panics
+    /// If `input` is longer than [`Self::MAX_INPUT_LEN`] or `out` is longer than `RATE`.
+    pub fn duplexing(&mut self, input: &[u8], out: &mut [u8]) {
+        self.duplexing_with_suffix(input, 0x01, out);
+    }
+
+    /// Absorb `input` followed by the delimited `suffix` and padded with `pad10*1`,
+    /// apply the permutation, and write the first `out.len()` bytes of the outer part
+    /// of the state into `out`.
+    ///
+    /// See [`Sponge`][crate::sponge::Sponge] for description of delimited suffixes.
+    ///
+    /// # Panics
+    /// If `suffix` is equal to zero, if `input` is longer than [`Self::MAX_INPUT_LEN`],
+    /// if `input` is [`Self::MAX_INPUT_LEN`] bytes long and `suffix` has the most
+    /// significant bit set, or if `out` is longer than `RATE`.
+    pub fn duplexing_with_suffix(&mut self, input: &[u8], suffix: u8, out: &mut [u8]) {
+        assert_ne!(suffix, 0, "delimited suffix must not be zero");
+        assert!(
+            input.len() < RATE - 1 || (input.len() == RATE - 1 && suffix & 0x80 == 0),
+            "input and suffix do not fit into the block",
+        );
+        assert!(out.len() <= RATE, "output is longer than rate");
+
+        if self.overwrite {
+            let mut block = [0u8; RATE];
+            block[..input.len()].copy_from_slice(input);
+            block[input.len()] ^= suffix;
+            block[RATE - 1] ^= 0x80;
+            (self.snp.overwrite_bytes)(&mut self.state, &block, 0);
+        } else {
+            (self.snp.add_bytes)(&mut self.state, input, 0);
+            (self.snp.add_bytes)(&mut self.state, &[suffix], input.len());
+            (self.snp.add_bytes)(&mut self.state, &[0x80], RATE - 1);
+        }
+        (self.p)(&mut self.state);
+        (self.snp.extract_bytes)(&self.state, out, 0);
+    }
+
+    /// Overwrite the whole state with `input` padded with `pad10*1` to `W::BYTE_LEN` bytes,
+    /// apply the permutation, and write the first `out.len()` bytes of the outer part
+    /// of the state into `out`.
+    ///
+    /// This is the full-state overwrite mode: the result does not depend on the inputs
+    /// absorbed before this call, so `input` must carry all secret material (e.g. a key)
+    /// required by the protocol.
+    ///
+    /// # Panics
+    /// If `input` is longer than [`Self::MAX_FULL_INPUT_LEN`] or `out` is longer than `RATE`.
+    pub fn duplexing_overwrite_full(&mut self, input: &[u8], out: &mut [u8]) {
+        assert!(
+            input.len() <= Self::MAX_FULL_INPUT_LEN,
+            "input does not fit into the state",
+        );
+        assert!(out.len() <= RATE, "output is longer than rate");
+
+        (self.snp.overwrite_with_zeroes)(&mut self.state, W::BYTE_LEN);
+        (self.snp.overwrite_bytes)(&mut self.state, input, 0);
+        (self.snp.add_bytes)(&mut self.state, &[0x01], input.len());
+        (self.snp.add_bytes)(&mut self.state, &[0x80], W::BYTE_LEN - 1);
+        (self.p)(&mut self.state);
+        (self.snp.extract_bytes)(&self.state, out, 0);
+    }
+}
+
+impl<W: Width, const RATE: usize, const ROUNDS: usize> Default for Duplex<W, RATE, ROUNDS> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<W: Width, const RATE: usize, const ROUNDS: usize> fmt::Debug for Duplex<W, RATE, ROUNDS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Duplex")
+            .field("rate", &RATE)
+            .field("rounds", &ROUNDS)
+            .field("overwrite", &self.overwrite)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Authentication tag verification error.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Error;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("authentication tag verification failed")
+    }
+}
+
+impl core::error::Error for Error {}
+
+/// SpongeWrap authenticated encryption mode over the [`Duplex`] construction.
+///
+/// Data is processed in blocks of `RATE - 1` bytes, so every block fits into a single
+/// duplexing call together with its frame bit. The same instance can be used to process
+/// a sequence of messages, in which case every tag authenticates all previous messages.
+#[derive(Clone)]
+pub struct SpongeWrap<W: Width, const RATE: usize, const ROUNDS: usize> {
+    duplex: Duplex<W, RATE, ROUNDS>,
+    poisoned: bool,
+}
+
+impl<W: Width, const RATE: usize, const ROUNDS: usize> SpongeWrap<W, RATE, ROUNDS> {
+    /// Minimum length of the authentication tag in bytes.
+    pub const MIN_TAG_LEN: usize = 16;
+
+    /// Data block size in bytes.
+    const BLOCK_LEN: usize = RATE - 1;
+
+    /// Create new SpongeWrap instance initialized with `key`.
+    #[must_use]
+    pub fn new(key: &[u8]) -> Self {
+        Self::new_with_keccak(Keccak::new(), key)
+    }
+
+    /// Create new SpongeWrap instance initialized with `key` using the provided
+    /// [`Keccak`] instance.
+    #[must_use]
+    pub fn new_with_keccak(keccak: Keccak, key: &[u8]) -> Self {
+        let mut duplex = Duplex::new_with_keccak(keccak, false);
+        let n = block_count(key.len(), Self::BLOCK_LEN);
+        for i in 0..n {
+            let frame = if i + 1 < n { FRAME_1 } else { FRAME_0 };
+            let block = &key[block_range(i, key.len(), Self::BLOCK_LEN)];
+            duplex.duplexing_with_suffix(block, frame, &mut []);
+        }
+        Self {
+            duplex,
+            poisoned: false,
+        }
+    }
+
+    /// Authenticate `header` and encrypt `data` in place, writing the tag into `tag`.
+    ///
+    /// # Panics
+    /// If `tag` is shorter than [`Self::MIN_TAG_LEN`] or if a previous call
+    /// to [`SpongeWrap::unwrap`] has failed.
+    pub fn wrap(&mut self, header: &[u8], data: &mut [u8], tag: &mut [u8]) {
+        assert!(tag.len() >= Self::MIN_TAG_LEN, "tag is too short");
+        assert!(
+            !self.poisoned,
+            "SpongeWrap instance used after failed unwrap"
+        );
+        let mut z = self.process(header, data, false);
+        self.squeeze_tag(&mut z, tag);
+    }
+
+    /// Authenticate `header` and decrypt `data` in place, checking it against `tag`.
+    ///
+    /// On tag mismatch `data` is zeroed and the instance becomes unusable. Tags shorter
+    /// than [`Self::MIN_TAG_LEN`] are rejected without processing `header` and `data`.
+    ///
+    /// # Errors
+    /// If `tag` is shorter than [`Self::MIN_TAG_LEN`] or the computed tag does not match `tag`.
+    ///
+    /// # Panics
+    /// If a previous call to [`SpongeWrap::unwrap`] has failed.
+    pub fn unwrap(&mut self, header: &[u8], data: &mut [u8], tag: &[u8]) -> Result<(), Error> {
+        assert!(
+            !self.poisoned,
+            "SpongeWrap instance used after failed unwrap"
+        );
+        if tag.len() < Self::MIN_TAG_LEN {
+            return Err(Error);
+        }
+        let mut z = self.process(header, data, true);
+
+        let mut diff = 0;
+        for (i, chunk) in tag.chunks(Self::BLOCK_LEN).enumerate() {
+            if i != 0 {
+                self.duplex
+                    .duplexing_with_suffix(&[], FRAME_0, &mut z[..Self::BLOCK_LEN]);
+            }
+            for (a, b) in chunk.iter().zip(&z) {
+                diff |= a ^ b;
+            }
+        }
+
+        if diff == 0 {
+            Ok(())
+        } else {
+            data.fill(0);
+            self.poisoned = true;
+            Err(Error)
+        }
+    }
+
+    /// Absorb `header` and encrypt or decrypt `data` in place.
+    ///
+    /// Returns the first block of the tag key stream.
+    fn process(&mut self, header: &[u8], data: &mut [u8], decrypt: bool) -> [u8; RATE] {
+        let bl = Self::BLOCK_LEN;
+        let mut z = [0u8; RATE];
+
+        let n = block_count(header.len(), bl);
+        for i in 0..n {
+            let block = &header[block_range(i, header.len(), bl)];
+            if i + 1 < n {
+                self.duplex.duplexing_with_suffix(block, FRAME_0, &mut []);
+            } else {
+                let z_len = block_range(0, data.len(), bl).len();
+                self.duplex
+                    .duplexing_with_suffix(block, FRAME_1, &mut z[..z_len]);
+            }
+        }
+
+        let len = data.len();
+        let n = block_count(len, bl);
+        for i in 0..n {
+            let block = &mut data[block_range(i, len, bl)];
+            let mut plaintext = [0u8; RATE];
+            let plaintext = &mut plaintext[..block.len()];
+            if !decrypt {
+                plaintext.copy_from_slice(block);
+            }
+            for (b, k) in block.iter_mut().zip(&z) {
+                *b ^= k;
+            }
+            if decrypt {
+                plaintext.copy_from_slice(block);
+            }
+
+            if i + 1 < n {
+                let z_len = block_range(i + 1, len, bl).len();
+                self.duplex
+                    .duplexing_with_suffix(plaintext, FRAME_1, &mut z[..z_len]);
+            } else {
+                self.duplex
+                    .duplexing_with_suffix(plaintext, FRAME_0, &mut z[..bl]);
+            }
+        }
+
+        z
+    }
+
+    /// Write tag into `tag` starting from the key stream block `z`.
+    fn squeeze_tag(&mut self, z: &mut [u8; RATE], tag: &mut [u8]) {
+        for (i, chunk) in tag.chunks_mut(Self::BLOCK_LEN).enumerate() {
+            if i != 0 {
+                self.duplex
+                    .duplexing_with_suffix(&[], FRAME_0, &mut z[..Self::BLOCK_LEN]);
+            }
+            chunk.copy_from_slice(&z[..chunk.len()]);
+        }
+    }
+}
+
+impl<W: Width, const RATE: usize, const ROUNDS: usize> fmt::Debug for SpongeWrap<W, RATE, ROUNDS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SpongeWrap")
+            .field("rate", &RATE)
+            .field("rounds", &ROUNDS)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Number of blocks used to process `len` bytes. Empty data is processed as a single
+/// empty block.
+fn block_count(len: usize, block_len: usize) -> usize {
+    len.div_ceil(block_len).max(1)
+}
+
+/// Byte range of the `i`-th block of data with length `len`.
+fn block_range(i: usize, len: usize, block_len: usize) -> core::ops::Range<usize> {
+    (i * block_len).min(len)..((i + 1) * block_len).min(len)
+}
@@ -0,0 +1,138 @@
+//! SHA-3 hash functions and SHAKE extendable-output functions defined in [FIPS 202].
+//!
+//! This module provides a minimal API without dependency on the `digest` traits.
+//! For a full-featured implementation see the [`sha3`] crate.
+//!
+//! [FIPS 202]: https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.202.pdf
+//! [`sha3`]: https://docs.rs/sha3
+use crate::consts::F1600_ROUNDS;
+use crate::{Keccak, State1600, sponge::Sponge};
+
+/// Delimited suffix of the SHA-3 hash functions (`01` followed by the padding bit).
+const SHA3_SUFFIX: u8 = 0x06;
+/// Delimited suffix of the SHAKE functions (`1111` followed by the padding bit).
+const SHAKE_SUFFIX: u8 = 0x1F;
+
+macro_rules! impl_sha3 {
+    ($name:ident, $fn_name:ident, $rate:expr, $out_len:expr, $alg:literal) => {
+        #[doc = concat!("The ", $alg, " hash function.")]
+        #[derive(Clone, Debug, Default)]
+        pub struct $name {
+            sponge: Sponge<State1600, $rate, F1600_ROUNDS>,
+        }
+
+        impl $name {
+            /// Rate of the sponge in bytes.
+            pub const RATE: usize = $rate;
+            /// Output length in bytes.
+            pub const OUTPUT_LEN: usize = $out_len;
+
+            #[doc = concat!("Create new ", $alg, " hasher.")]
+            #[must_use]
+            pub fn new() -> Self {
+                Self::default()
+            }
+
+            #[doc = concat!("Create new ", $alg, " hasher using the provided [`Keccak`] instance.")]
+            #[must_use]
+            pub fn new_with_keccak(keccak: Keccak) -> Self {
+                Self {
+                    sponge: Sponge::new_with_keccak(keccak),
+                }
+            }
+
+            /// Absorb `data` into the hasher state.
+            pub fn update(&mut self, data: &[u8]) {
+                self.sponge.absorb(data);
+            }
+
+            /// Finalize the hasher and return the hash.
+            #[must_use]
+            pub fn finalize(mut self) -> [u8; $out_len] {
+                let mut out = [0u8; $out_len];
+                self.sponge.absorb_last(SHA3_SUFFIX);
+                self.sponge.squeeze(&mut out);
+                out
+            }
+        }
+
+        #[doc = concat!("Compute ", $alg, " hash of `data`.")]
+        #[must_use]
+        pub fn $fn_name(data: &[u8]) -> [u8; $out_len] {
+            let mut hasher = $name::new();
+            hasher.update(data);
+            hasher.finalize()
+        }
+    };
+}
+
+impl_sha3!(Sha3_224, sha3_224, 144, 28, "SHA3-224");
+impl_sha3!(Sha3_256, sha3_256, 136, 32, "SHA3-256");
+impl_sha3!(Sha3_384, sha3_384, 104, 48, "SHA3-384");
+impl_sha3!(Sha3_512, sha3_512, 72, 64, "SHA3-512");
+
+macro_rules! impl_shake {
+    ($name:ident, $reader:ident, $fn_name:ident, $rate:expr, $alg:literal) => {
+        #[doc = concat!("The ", $alg, " extendable-output function.")]
+        #[derive(Clone, Debug, Default)]
+        pub struct $name {
+            sponge: Sponge<State1600, $rate, F1600_ROUNDS>,
+        }
+
+        impl $name {
+            /// Rate of the sponge in bytes.
+            pub const RATE: usize = $rate;
+
+            #[doc = concat!("Create new ", $alg, " hasher.")]
+            #[must_use]
+            pub fn new() -> Self {
+                Self::default()
+            }
+
+            #[doc = concat!("Create new ", $alg, " hasher using the provided [`Keccak`] instance.")]
+            #[must_use]
+            pub fn new_with_keccak(keccak: Keccak) -> Self {
+                Self {
+                    sponge: Sponge::new_with_keccak(keccak),
+                }
+            }
+
+            /// Absorb `data` into the hasher state.
+            pub fn update(&mut self, data: &[u8]) {
+                self.sponge.absorb(data);
+            }
+
+            /// Finalize the hasher and return reader of the output stream.
+            #[must_use]
+            pub fn finalize_xof(mut self) -> $reader {
+                self.sponge.absorb_last(SHAKE_SUFFIX);
+                $reader {
+                    sponge: self.sponge,
+                }
+            }
+        }
+
+        #[doc = concat!("Reader of the ", $alg, " output stream.")]
+        #[derive(Clone, Debug)]
+        pub struct $reader {
+            sponge: Sponge<State1600, $rate, F1600_ROUNDS>,
+        }
+
+        impl $reader {
+            /// Read the next `out.len()` bytes of the output stream.
+            pub fn read(&mut self, out: &mut [u8]) {
+                self.sponge.squeeze(out);
+            }
+        }
+
+        #[doc = concat!("Compute ", $alg, " of `data` and write `out.len()` bytes of output into `out`.")]
+        pub fn $fn_name(data: &[u8], out: &mut [u8]) {
+            let mut hasher = $name::new();
+            hasher.update(data);
+            hasher.finalize_xof().read(out);
+        }
+    };
+}
+
+impl_shake!(Shake128, Shake128Reader, shake128, 168, "SHAKE128");
+impl_shake!(Shake256, Shake256Reader, shake256, 136, "SHAKE256");
@@ -0,0 +1,108 @@
+//! Keccak-p permutations over user-defined lane types.
+//!
+//! [`keccak_p`] implements Keccak-p over any type implementing [`LaneSize`]. Besides
+//! the primitive unsigned integers, lanes can be e.g. several states packed into one word,
+//! bitsliced words, masked shares, or handles of symbolic expressions.
+//!
+//! Lanes of `w` bits, where `w` is a power of two in the `1..=64` range, give the
+//! Keccak-p\[25w\] permutations. Rotation offsets of rho are reduced modulo `w`,
+//! and round constants are truncated to the lower `w` bits, as defined in FIPS 202.
+use crate::consts::PLEN;
+use crate::{Keccak, backends::soft};
+use core::ops::{BitAnd, BitAndAssign, BitOr, BitXor, BitXorAssign, Not};
+
+/// Lane of the Keccak state.
+///
+/// Keccak is a permutation over an array of lanes which comprise the sponge
+/// construction. Implementations must behave like `BITS`-bit words with bitwise
+/// operations working on all bits independently.
+pub trait LaneSize:
+    Copy
+    + Clone
+    + Default
+    + BitAndAssign
+    + BitAnd<Output = Self>
+    + BitOr<Output = Self>
+    + BitXorAssign
+    + BitXor<Output = Self>
+    + Not<Output = Self>
+{
+    /// Number of bits in the lane. Must be a power of two not bigger than 64.
+    const BITS: u32;
+
+    /// Create lane from the lower `BITS` bits of the round constant `rc`.
+    fn truncate_rc(rc: u64) -> Self;
+
+    /// Rotate the lane left by `n` bits, where `n` is smaller than `BITS`.
+    #[must_use]
+    fn rotate_left(self, n: u32) -> Self;
+
+    /// Get `p` function over lanes of this type with the specified number of rounds.
+    ///
+    /// Returns [`keccak_p`] by default. The primitive unsigned integers use
+    /// the backend selected by `keccak`.
+    #[inline]
+    #[must_use]
+    fn get_p<const ROUNDS: usize>(_keccak: &Keccak) -> fn(&mut [Self; PLEN]) {
+        keccak_p::<Self, ROUNDS>
+    }
+}
+
+macro_rules! impl_lanesize {
+    ($type:ty, $with_p:ident) => {
+        impl LaneSize for $type {
+            const BITS: u32 = <$type>::BITS;
+
+            #[allow(clippy::cast_possible_truncation, trivial_numeric_casts)]
+            fn truncate_rc(rc: u64) -> Self {
+                rc as Self
+            }
+
+            fn rotate_left(self, n: u32) -> Self {
+                self.rotate_left(n)
+            }
+
+            #[inline]
+            fn get_p<const ROUNDS: usize>(keccak: &Keccak) -> fn(&mut [Self; PLEN]) {
+                let mut res: fn(&mut [Self; PLEN]) = |_| {};
+                keccak.$with_p::<ROUNDS>(|p| res = p);
+                res
+            }
+        }
+    };
+}
+
+impl_lanesize!(u8, with_p200);
+impl_lanesize!(u16, with_p400);
+impl_lanesize!(u32, with_p800);
+impl_lanesize!(u64, with_p1600);
+
+/// Number of rounds of the Keccak-f permutation over lanes of type `L`.
+#[must_use]
+pub const fn keccak_f_round_count<L: LaneSize>() -> usize {
+    const { assert_lane_bits::<L>() };
+    round_count(L::BITS)
+}
+
+/// Check that [`LaneSize::BITS`] of `L` is a power of two not bigger than 64.
+const fn assert_lane_bits<L: LaneSize>() {
+    assert!(
+        L::BITS.is_power_of_two() && L::BITS <= 64,
+        "lane size must be a power of two not bigger than 64 bits",
+    );
+}
+
+/// Number of rounds of Keccak-f over lanes of `bits` bits, i.e. `12 + 2 * log2(bits)`.
+const fn round_count(bits: u32) -> usize {
+    12 + 2 * bits.trailing_zeros() as usize
+}
+
+/// Apply Keccak-p with `ROUNDS` rounds over lanes of type `L` to `state`.
+///
+/// If `ROUNDS` is bigger than [`keccak_f_round_count::<L>()`][keccak_f_round_count],
+/// the Keccak-f rounds are preceded by rounds with negative indices.
+#[inline]
+pub fn keccak_p<L: LaneSize, const ROUNDS: usize>(state: &mut [L; PLEN]) {
+    const { assert_lane_bits::<L>() };
+    soft::keccak_p::<L, ROUNDS>(state);
+}
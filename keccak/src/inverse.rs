@@ -0,0 +1,181 @@
+//! Inverse Keccak-p permutations.
+//!
+//! The functions in this module undo the corresponding Keccak-p permutations round
+//! by round by applying inverse iota, chi, rho and pi, and theta steps. They are
+//! intended for cryptanalysis and experiments with the bare permutation and are not
+//! optimized for performance.
+//!
+//! The inverse of chi is computed with the closed formula
+//! `a[x] = b[x] ^ !b[x + 1] & (b[x + 2] ^ !b[x + 3] & b[x + 4])`.
+//!
+//! Theta adds `D = (t + t^4 z) C` to every row, where `C` is the column parity
+//! represented as an element of `GF(2)[t, z] / (t^5 + 1, z^w + 1)` with `w` being
+//! the lane size. Column parity after theta is equal to `C' = (1 + t + t^4 z) C`,
+//! so the inverse recovers `C` by multiplying `C'` by the inverse of `1 + t + t^4 z`,
+//! which is computed at compile time.
+use crate::consts::{
+    F200_ROUNDS, F400_ROUNDS, F800_ROUNDS, F1600_ROUNDS, PI, RHO, round_constants,
+};
+use crate::{State200, State400, State800, State1600};
+
+/// Rotate the lower `w` bits of `x` to the left by `r` bits.
+const fn rotl(x: u64, r: u32, w: u32) -> u64 {
+    let mask = u64::MAX >> (64 - w);
+    let r = r % w;
+    if r == 0 {
+        x & mask
+    } else {
+        ((x << r) | ((x & mask) >> (w - r))) & mask
+    }
+}
+
+/// Multiply two elements of `GF(2)[t, z] / (t^5 + 1, z^w + 1)`.
+///
+/// Bit `r` of `a[k]` is the coefficient of `t^k z^r`.
+const fn poly_mul(a: [u64; 5], b: [u64; 5], w: u32) -> [u64; 5] {
+    let mut res = [0; 5];
+    let mut i = 0;
+    while i < 5 {
+        let mut j = 0;
+        while j < 5 {
+            let mut r = 0;
+            while r < w {
+                if (b[j] >> r) & 1 == 1 {
+                    res[(i + j) % 5] ^= rotl(a[i], r, w);
+                }
+                r += 1;
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+    res
+}
+
+/// Compute the inverse of the column parity transform `1 + t + t^4 z` of theta
+/// for lanes of `w` bits.
+///
+/// Squaring is linear over `GF(2)`, so raising `P = 1 + t + t^4 z` to the power of
+/// `2^8` gives `1 + t + t^4` for all lane sizes up to 64 bits. The unit group of
+/// `GF(2)[t] / (t^5 + 1)` has 15 elements, thus `P^(15 * 2^8) = 1` and the inverse
+/// is equal to `P^(15 * 2^8 - 1)`.
+pub(crate) const fn theta_inverse_coeffs(w: u32) -> [u64; 5] {
+    let mut base = [1, 1, 0, 0, 1 << (1 % w)];
+    let mut exp: u32 = 15 * (1 << 8) - 1;
+    let mut res = [1, 0, 0, 0, 0];
+    while exp != 0 {
+        if exp & 1 == 1 {
+            res = poly_mul(res, base, w);
+        }
+        base = poly_mul(base, base, w);
+        exp >>= 1;
+    }
+    res
+}
+
+macro_rules! impl_inverse_p {
+    ($inv_p:ident, $inv_f:ident, $state:ty, $lane:ty, $f_rounds:expr, $width:literal) => {
+        #[doc = concat!("Apply the inverse of the Keccak-p\\[", $width, "\\] permutation with `ROUNDS` rounds to `state`.")]
+        #[allow(clippy::cast_possible_truncation, trivial_numeric_casts)]
+        pub const fn $inv_p<const ROUNDS: usize>(state: &mut $state) {
+            const THETA_INV: [u64; 5] = theta_inverse_coeffs(<$lane>::BITS);
+
+            let round_consts = const { round_constants::<ROUNDS>($f_rounds) };
+            let mut round = ROUNDS;
+            while round > 0 {
+                round -= 1;
+
+                // Iota
+                state[0] ^= round_consts[round] as $lane;
+
+                // Chi
+                let mut y = 0;
+                while y < 25 {
+                    let b = [state[y], state[y + 1], state[y + 2], state[y + 3], state[y + 4]];
+                    let mut x = 0;
+                    while x < 5 {
+                        let t = b[(x + 2) % 5] ^ (!b[(x + 3) % 5] & b[(x + 4) % 5]);
+                        state[y + x] = b[x] ^ (!b[(x + 1) % 5] & t);
+                        x += 1;
+                    }
+                    y += 5;
+                }
+
+                // Rho and pi
+                let b = *state;
+                let mut i = 0;
+                while i < 24 {
+                    let dst = if i == 0 { 1 } else { PI[i - 1] };
+                    state[dst] = b[PI[i]].rotate_right(RHO[i]);
+                    i += 1;
+                }
+
+                // Theta
+                let mut c_theta = [0; 5];
+                let mut x = 0;
+                while x < 5 {
+                    c_theta[x] =
+                        state[x] ^ state[x + 5] ^ state[x + 10] ^ state[x + 15] ^ state[x + 20];
+                    x += 1;
+                }
+                let mut c = [0 as $lane; 5];
+                let mut k = 0;
+                while k < 5 {
+                    let mut r = 0;
+                    while r < <$lane>::BITS {
+                        if (THETA_INV[k] >> r) & 1 == 1 {
+                            let mut x = 0;
+                            while x < 5 {
+                                c[x] ^= c_theta[(x + 5 - k) % 5].rotate_left(r);
+                                x += 1;
+                            }
+                        }
+                        r += 1;
+                    }
+                    k += 1;
+                }
+                let mut x = 0;
+                while x < 5 {
+                    let d = c[x] ^ c_theta[x];
+                    let mut y = 0;
+                    while y < 25 {
+                        state[y + x] ^= d;
+                        y += 5;
+                    }
+                    x += 1;
+                }
+            }
+        }
+
+        #[doc = concat!("Apply the inverse of the Keccak-f\\[", $width, "\\] permutation to `state`.")]
+        pub const fn $inv_f(state: &mut $state) {
+            $inv_p::<$f_rounds>(state);
+        }
+    };
+}
+
+impl_inverse_p!(inverse_p200, inverse_f200, State200, u8, F200_ROUNDS, "200");
+impl_inverse_p!(
+    inverse_p400,
+    inverse_f400,
+    State400,
+    u16,
+    F400_ROUNDS,
+    "400"
+);
+impl_inverse_p!(
+    inverse_p800,
+    inverse_f800,
+    State800,
+    u32,
+    F800_ROUNDS,
+    "800"
+);
+impl_inverse_p!(
+    inverse_p1600,
+    inverse_f1600,
+    State1600,
+    u64,
+    F1600_ROUNDS,
+    "1600"
+);
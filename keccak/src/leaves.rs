@@ -0,0 +1,283 @@
+//! Hashing of independent tree leaves shared by ParallelHash, KangarooTwelve, and Sakura trees.
+use crate::{Backend, BackendClosure, Keccak, State1600, sponge::Sponge};
+
+/// Incremental hasher of a sequence of leaves with the fixed length of `leaf_len` bytes
+/// (except the last one, which may be shorter).
+///
+/// Every leaf is hashed with a sponge over [`State1600`] with rate of `RATE` bytes,
+/// Keccak-p\[1600\] with `ROUNDS` rounds, and the delimited `suffix`. The first `CV_LEN`
+/// bytes of output form the chaining value of the leaf.
+///
+/// Whole leaves passed to a single [`Leaves::update`] call are hashed in batches using
+/// [`Backend::get_par_p1600`] if the backend supports more than one parallel state.
+/// With the `rayon` feature enabled, large runs of whole leaves are additionally split
+/// between threads.
+#[derive(Clone, Debug)]
+pub(crate) struct Leaves<const RATE: usize, const ROUNDS: usize, const CV_LEN: usize> {
+    keccak: Keccak,
+    leaf_len: usize,
+    suffix: u8,
+    leaf: Sponge<State1600, RATE, ROUNDS>,
+    leaf_pos: usize,
+    count: u64,
+}
+
+impl<const RATE: usize, const ROUNDS: usize, const CV_LEN: usize> Leaves<RATE, ROUNDS, CV_LEN> {
+    /// Create new leaf hasher.
+    ///
+    /// # Panics
+    /// If `leaf_len` is equal to zero or if `suffix` is not in the `0x01..=0x7F` range.
+    pub(crate) fn new(keccak: Keccak, leaf_len: usize, suffix: u8) -> Self {
+        const {
+            assert!(RATE % 8 == 0, "rate must consist of whole lanes");
+            assert!(CV_LEN <= RATE, "chaining value must fit into one block");
+        }
+        assert_ne!(leaf_len, 0, "leaf length must not be zero");
+        // Suffixes with the most significant bit set may need an extra block
+        assert!((0x01..=0x7F).contains(&suffix), "invalid leaf suffix");
+        Self {
+            keccak,
+            leaf_len,
+            suffix,
+            leaf: Sponge::new_with_keccak(keccak),
+            leaf_pos: 0,
+            count: 0,
+        }
+    }
+
+    /// Number of leaves whose chaining values were passed into a sink.
+    pub(crate) fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Absorb `data` into the leaves and pass chaining values of all completed
+    /// leaves into `sink` in order.
+    pub(crate) fn update(&mut self, mut data: &[u8], mut sink: impl FnMut(&[u8])) {
+        if self.leaf_pos != 0 {
+            let n = (self.leaf_len - self.leaf_pos).min(data.len());
+            self.leaf.absorb(&data[..n]);
+            self.leaf_pos += n;
+            data = &data[n..];
+            if self.leaf_pos == self.leaf_len {
+                self.finish(&mut sink);
+            }
+        }
+        if data.is_empty() {
+            return;
+        }
+
+        let whole_len = data.len() - data.len() % self.leaf_len;
+        let (whole, tail) = data.split_at(whole_len);
+        let count = &mut self.count;
+        let sink = |cv: &[u8]| {
+            sink(cv);
+            *count += 1;
+        };
+
+        #[cfg(feature = "rayon")]
+        if whole.len() >= 2 * MIN_TASK_LEN {
+            hash_whole_threaded::<RATE, ROUNDS, CV_LEN>(
+                self.keccak,
+                self.leaf_len,
+                self.suffix,
+                whole,
+                sink,
+            );
+        } else {
+            hash_whole::<RATE, ROUNDS, CV_LEN>(
+                self.keccak,
+                self.leaf_len,
+                self.suffix,
+                whole,
+                sink,
+            );
+        }
+        #[cfg(not(feature = "rayon"))]
+        hash_whole::<RATE, ROUNDS, CV_LEN>(self.keccak, self.leaf_len, self.suffix, whole, sink);
+
+        if !tail.is_empty() {
+            self.leaf.absorb(tail);
+            self.leaf_pos = tail.len();
+        }
+    }
+
+    /// Pass chaining value of the current partial leaf (if any) into `sink`.
+    pub(crate) fn finish(&mut self, mut sink: impl FnMut(&[u8])) {
+        if self.leaf_pos == 0 {
+            return;
+        }
+        let mut cv = [0u8; CV_LEN];
+        self.leaf.absorb_last(self.suffix);
+        self.leaf.squeeze(&mut cv);
+        sink(&cv);
+        self.leaf = Sponge::new_with_keccak(self.keccak);
+        self.leaf_pos = 0;
+        self.count += 1;
+    }
+}
+
+/// Minimum amount of data in bytes hashed by a single rayon task.
+#[cfg(feature = "rayon")]
+const MIN_TASK_LEN: usize = 1 << 16;
+/// Maximum number of rayon tasks whose chaining values are buffered at once.
+#[cfg(feature = "rayon")]
+const SEGMENT_TASKS: usize = 256;
+/// Maximum size in bytes of the buffered chaining values.
+#[cfg(feature = "rayon")]
+const SEGMENT_CVS_LEN: usize = 1 << 22;
+
+/// Hash whole leaves of `data` on the current thread and pass their chaining values
+/// into `sink` in order.
+#[inline]
+fn hash_whole<const RATE: usize, const ROUNDS: usize, const CV_LEN: usize>(
+    keccak: Keccak,
+    leaf_len: usize,
+    suffix: u8,
+    data: &[u8],
+    sink: impl FnMut(&[u8]),
+) {
+    keccak.with_backend(LeavesClosure::<_, RATE, ROUNDS, CV_LEN> {
+        data,
+        leaf_len,
+        suffix,
+        sink,
+    });
+}
+
+/// Hash whole leaves of `data` on the rayon thread pool and pass their chaining values
+/// into `sink` in order.
+///
+/// Every task processes a contiguous run of leaves with [`hash_whole`], so the chaining
+/// values are identical to the single-threaded ones. Data is processed in segments of
+/// at most `SEGMENT_TASKS` tasks whose chaining values fit into `SEGMENT_CVS_LEN` bytes.
+#[cfg(feature = "rayon")]
+fn hash_whole_threaded<const RATE: usize, const ROUNDS: usize, const CV_LEN: usize>(
+    keccak: Keccak,
+    leaf_len: usize,
+    suffix: u8,
+    data: &[u8],
+    mut sink: impl FnMut(&[u8]),
+) {
+    use rayon::prelude::*;
+
+    let max_task_leaves = (SEGMENT_CVS_LEN / CV_LEN / SEGMENT_TASKS).max(1);
+    let task_leaves = MIN_TASK_LEN.div_ceil(leaf_len).min(max_task_leaves);
+    let segment_leaves = SEGMENT_TASKS * task_leaves;
+    let segment_len = segment_leaves * leaf_len;
+    let mut cvs = alloc::vec![0u8; (data.len() / leaf_len).min(segment_leaves) * CV_LEN];
+
+    for segment in data.chunks(segment_len) {
+        let cvs = &mut cvs[..segment.len() / leaf_len * CV_LEN];
+        segment
+            .par_chunks(task_leaves * leaf_len)
+            .zip(cvs.par_chunks_mut(task_leaves * CV_LEN))
+            .for_each(|(leaves, out)| {
+                let mut out = out.chunks_exact_mut(CV_LEN);
+                hash_whole::<RATE, ROUNDS, CV_LEN>(keccak, leaf_len, suffix, leaves, |cv| {
+                    if let Some(dst) = out.next() {
+                        dst.copy_from_slice(cv);
+                    }
+                });
+            });
+        cvs.chunks_exact(CV_LEN).for_each(&mut sink);
+    }
+}
+
+/// Hash a single leaf and return its chaining value.
+#[inline(always)]
+fn hash_leaf<B: Backend, const RATE: usize, const ROUNDS: usize, const CV_LEN: usize>(
+    leaf: &[u8],
+    suffix: u8,
+) -> [u8; CV_LEN] {
+    let mut state = State1600::default();
+    let n = B::fast_loop_absorb_p1600::<ROUNDS>(&mut state, RATE / 8, leaf);
+    let tail = &leaf[n..];
+    B::add_bytes1600(&mut state, tail, 0);
+    B::add_bytes1600(&mut state, &[suffix], tail.len());
+    B::add_bytes1600(&mut state, &[0x80], RATE - 1);
+    B::get_p1600::<ROUNDS>()(&mut state);
+
+    let mut cv = [0u8; CV_LEN];
+    B::extract_bytes1600(&state, &mut cv, 0);
+    cv
+}
+
+/// Hash groups of `B::ParSize1600` leaves of `leaf_len` bytes each using the parallel
+/// `p1600` function and pass chaining values into `sink`.
+///
+/// Returns the remaining leaves which do not fill a whole group.
+#[cfg(feature = "parallel")]
+#[inline(always)]
+fn hash_leaves_par<'a, B: Backend, const RATE: usize, const ROUNDS: usize, const CV_LEN: usize>(
+    data: &'a [u8],
+    leaf_len: usize,
+    suffix: u8,
+    sink: &mut impl FnMut(&[u8]),
+) -> &'a [u8] {
+    use hybrid_array::typenum::Unsigned;
+
+    let par_size = <B::ParSize1600 as Unsigned>::USIZE;
+    if par_size == 1 {
+        return data;
+    }
+
+    let par_p = B::get_par_p1600::<ROUNDS>();
+    let mut groups = data.chunks_exact(par_size * leaf_len);
+    for group in &mut groups {
+        let mut states = crate::ParState1600::<B>::default();
+        let leaf = |i: usize| &group[i * leaf_len..][..leaf_len];
+
+        let mut pos = 0;
+        while leaf_len - pos >= RATE {
+            for (i, state) in states.iter_mut().enumerate() {
+                B::add_bytes1600(state, &leaf(i)[pos..][..RATE], 0);
+            }
+            par_p(&mut states);
+            pos += RATE;
+        }
+
+        let tail_len = leaf_len - pos;
+        for (i, state) in states.iter_mut().enumerate() {
+            B::add_bytes1600(state, &leaf(i)[pos..], 0);
+            B::add_bytes1600(state, &[suffix], tail_len);
+            B::add_bytes1600(state, &[0x80], RATE - 1);
+        }
+        par_p(&mut states);
+
+        for state in &states {
+            let mut cv = [0u8; CV_LEN];
+            B::extract_bytes1600(state, &mut cv, 0);
+            sink(&cv);
+        }
+    }
+    groups.remainder()
+}
+
+/// Hash whole leaves of `data` and pass their chaining values into `sink` in order.
+struct LeavesClosure<'a, F, const RATE: usize, const ROUNDS: usize, const CV_LEN: usize> {
+    data: &'a [u8],
+    leaf_len: usize,
+    suffix: u8,
+    sink: F,
+}
+
+impl<F: FnMut(&[u8]), const RATE: usize, const ROUNDS: usize, const CV_LEN: usize> BackendClosure
+    for LeavesClosure<'_, F, RATE, ROUNDS, CV_LEN>
+{
+    #[inline(always)]
+    fn call_once<B: Backend>(mut self) {
+        #[cfg(feature = "parallel")]
+        let data = hash_leaves_par::<B, RATE, ROUNDS, CV_LEN>(
+            self.data,
+            self.leaf_len,
+            self.suffix,
+            &mut self.sink,
+        );
+        #[cfg(not(feature = "parallel"))]
+        let data = self.data;
+
+        for leaf in data.chunks_exact(self.leaf_len) {
+            (self.sink)(&hash_leaf::<B, RATE, ROUNDS, CV_LEN>(leaf, self.suffix));
+        }
+    }
+}
@@ -14,14 +14,39 @@
     html_favicon_url = "https://raw.githubusercontent.com/RustCrypto/meta/master/logo.svg"
 )]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
 #[cfg(target_arch = "aarch64")]
 cpufeatures::new!(armv8_sha3_intrinsics, "sha3");
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+cpufeatures::new!(x86_avx2_intrinsics, "avx2");
+#[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), keccak_avx512))]
+cpufeatures::new!(x86_avx512_intrinsics, "avx512f", "avx512vl");
 
+#[cfg(feature = "alloc")]
+pub mod analysis;
 pub mod backends;
+pub mod bytes;
+pub mod const_fn;
 pub mod consts;
+pub mod duplex;
+pub mod fips202;
+pub mod generic;
+pub mod inverse;
+mod leaves;
+pub mod rfc9861;
+pub mod sakura;
+pub mod sp800_185;
+pub mod sponge;
+pub mod steps;
+pub mod trails;
 pub mod types;
 
 pub use backends::*;
+pub use bytes::*;
 pub use consts::*;
 pub use types::*;
 
@@ -30,6 +55,10 @@ pub use types::*;
 pub struct Keccak {
     #[cfg(target_arch = "aarch64")]
     armv8_sha3: armv8_sha3_intrinsics::InitToken,
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    x86_avx2: x86_avx2_intrinsics::InitToken,
+    #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), keccak_avx512))]
+    x86_avx512: x86_avx512_intrinsics::InitToken,
 }
 
 impl Default for Keccak {
@@ -38,6 +67,10 @@ impl Default for Keccak {
         Self {
             #[cfg(target_arch = "aarch64")]
             armv8_sha3: armv8_sha3_intrinsics::init(),
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            x86_avx2: x86_avx2_intrinsics::init(),
+            #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), keccak_avx512))]
+            x86_avx512: x86_avx512_intrinsics::init(),
         }
     }
 }
@@ -69,8 +102,35 @@ impl Keccak {
                 compile_error!("aarch64_sha3 backend requires sha3 target feature to be enabled!");
 
                 return f.call_once::<aarch64_sha3::Backend>()
+            } else if #[cfg(keccak_backend = "x86_avx2")] {
+                #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+                compile_error!("x86_avx2 backend can be used only on x86 and x86-64 targets!");
+                #[cfg(not(target_feature = "avx2"))]
+                compile_error!("x86_avx2 backend requires avx2 target feature to be enabled!");
+
+                return f.call_once::<x86_avx2::Backend>()
+            } else if #[cfg(keccak_backend = "x86_avx512")] {
+                #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+                compile_error!("x86_avx512 backend can be used only on x86 and x86-64 targets!");
+                #[cfg(not(keccak_avx512))]
+                compile_error!("x86_avx512 backend requires Rust 1.89 or newer!");
+                #[cfg(not(all(target_feature = "avx512f", target_feature = "avx512vl")))]
+                compile_error!(
+                    "x86_avx512 backend requires avx512f and avx512vl target features to be enabled!"
+                );
+
+                return f.call_once::<x86_avx512::Backend>()
+            } else if #[cfg(keccak_backend = "x86_sse2")] {
+                #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+                compile_error!("x86_sse2 backend can be used only on x86 and x86-64 targets!");
+                #[cfg(not(target_feature = "sse2"))]
+                compile_error!("x86_sse2 backend requires sse2 target feature to be enabled!");
+
+                return f.call_once::<x86_sse2::Backend>()
             } else if #[cfg(keccak_backend = "soft")] {
                 return f.call_once::<soft::Backend>()
+            } else if #[cfg(keccak_backend = "soft_interleaved")] {
+                return f.call_once::<soft_interleaved::Backend>()
             }
         );
 
@@ -84,7 +144,55 @@ impl Keccak {
             return unsafe { aarch64_sha3_inner(f) };
         }
 
-        f.call_once::<soft::Backend>();
+        #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), keccak_avx512))]
+        if self.x86_avx512.get() {
+            #[target_feature(enable = "avx512f,avx512vl")]
+            unsafe fn x86_avx512_inner(f: impl BackendClosure) {
+                f.call_once::<x86_avx512::Backend>();
+            }
+            // SAFETY: we checked target feature availability above
+            return unsafe { x86_avx512_inner(f) };
+        }
+
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        if self.x86_avx2.get() {
+            #[target_feature(enable = "avx2")]
+            unsafe fn x86_avx2_inner(f: impl BackendClosure) {
+                f.call_once::<x86_avx2::Backend>();
+            }
+            // SAFETY: we checked target feature availability above
+            return unsafe { x86_avx2_inner(f) };
+        }
+
+        // SSE2 is enabled by default on x86-64 targets
+        #[cfg(all(
+            any(target_arch = "x86", target_arch = "x86_64"),
+            target_feature = "sse2"
+        ))]
+        return f.call_once::<x86_sse2::Backend>();
+
+        f.call_once::<ScalarBackend>();
+    }
+
+    /// Execute the backend closure with the AVX2 backend if the `avx2` extension
+    /// is available and return `true`, otherwise return `false`.
+    ///
+    /// Used to test the AVX2 backend on CPUs where [`Keccak::with_backend`] selects
+    /// a wider backend.
+    #[doc(hidden)]
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    pub fn with_x86_avx2(&self, f: impl BackendClosure) -> bool {
+        if !self.x86_avx2.get() {
+            return false;
+        }
+
+        #[target_feature(enable = "avx2")]
+        unsafe fn x86_avx2_inner(f: impl BackendClosure) {
+            f.call_once::<x86_avx2::Backend>();
+        }
+        // SAFETY: we checked target feature availability above
+        unsafe { x86_avx2_inner(f) };
+        true
     }
 
     /// Execute the closure with `f200` function.
@@ -111,37 +219,66 @@ impl Keccak {
         self.with_p1600::<F1600_ROUNDS>(f);
     }
 
-    /// Execute the closure with `p200` function with the specified number of rounds.
+    /// Execute the closure with `p` function over lanes of type `L` with the specified
+    /// number of rounds.
     ///
-    /// # Panics
-    /// If `ROUNDS` is bigger than [`F200_ROUNDS`].
+    /// For the primitive unsigned integers this is equivalent to the width-specific
+    /// methods, e.g. [`Keccak::with_p1600`] for `u64`. Other lane types use
+    /// [`generic::keccak_p`] unless they override [`generic::LaneSize::get_p`].
+    #[inline]
+    pub fn with_p<L: generic::LaneSize, const ROUNDS: usize>(
+        &self,
+        f: impl FnOnce(fn(&mut [L; PLEN])),
+    ) {
+        f(L::get_p::<ROUNDS>(self));
+    }
+
+    /// Execute the closure with `p200` function with the specified number of rounds.
     #[inline]
     pub fn with_p200<const ROUNDS: usize>(&self, f: impl FnOnce(Fn200)) {
-        f(soft::keccak_p::<u8, ROUNDS>);
+        struct Closure<const ROUNDS: usize, F: FnOnce(Fn200)>(F);
+
+        impl<const ROUNDS: usize, F: FnOnce(Fn200)> BackendClosure for Closure<ROUNDS, F> {
+            #[inline(always)]
+            fn call_once<B: Backend>(self) {
+                (self.0)(B::get_p200::<ROUNDS>());
+            }
+        }
+
+        self.with_backend(Closure::<ROUNDS, _>(f));
     }
 
     /// Execute the closure with `p200` function with the specified number of rounds.
-    ///
-    /// # Panics
-    /// If `ROUNDS` is bigger than [`F400_ROUNDS`].
     #[inline]
     pub fn with_p400<const ROUNDS: usize>(&self, f: impl FnOnce(Fn400)) {
-        f(soft::keccak_p::<u16, ROUNDS>);
+        struct Closure<const ROUNDS: usize, F: FnOnce(Fn400)>(F);
+
+        impl<const ROUNDS: usize, F: FnOnce(Fn400)> BackendClosure for Closure<ROUNDS, F> {
+            #[inline(always)]
+            fn call_once<B: Backend>(self) {
+                (self.0)(B::get_p400::<ROUNDS>());
+            }
+        }
+
+        self.with_backend(Closure::<ROUNDS, _>(f));
     }
 
     /// Execute the closure with `p800` function with the specified number of rounds.
-    ///
-    /// # Panics
-    /// If `ROUNDS` is bigger than [`F800_ROUNDS`].
     #[inline]
     pub fn with_p800<const ROUNDS: usize>(&self, f: impl FnOnce(Fn800)) {
-        f(soft::keccak_p::<u32, ROUNDS>);
+        struct Closure<const ROUNDS: usize, F: FnOnce(Fn800)>(F);
+
+        impl<const ROUNDS: usize, F: FnOnce(Fn800)> BackendClosure for Closure<ROUNDS, F> {
+            #[inline(always)]
+            fn call_once<B: Backend>(self) {
+                (self.0)(B::get_p800::<ROUNDS>());
+            }
+        }
+
+        self.with_backend(Closure::<ROUNDS, _>(f));
     }
 
     /// Execute the closure with `p1600` function with the specified number of rounds.
-    ///
-    /// # Panics
-    /// If `ROUNDS` is bigger than [`F1600_ROUNDS`].
     #[inline]
     pub fn with_p1600<const ROUNDS: usize>(&self, f: impl FnOnce(Fn1600)) {
         struct Closure<const ROUNDS: usize, F: FnOnce(Fn1600)>(F);
@@ -155,4 +292,41 @@ impl Keccak {
 
         self.with_backend(Closure::<ROUNDS, _>(f));
     }
+
+    /// Execute the closure with `p1600` function with the number of rounds specified
+    /// at runtime.
+    ///
+    /// This is an alternative to [`Keccak::with_p1600`] for protocols which negotiate
+    /// the number of rounds at runtime. Numbers of rounds bigger than [`MAX_RUNTIME_ROUNDS`]
+    /// are available only through [`Keccak::with_p1600`].
+    ///
+    /// # Errors
+    /// If `rounds` is bigger than [`MAX_RUNTIME_ROUNDS`]. The closure is not executed in this case.
+    #[inline]
+    pub fn with_p1600_rounds(
+        &self,
+        rounds: usize,
+        f: impl FnOnce(Fn1600),
+    ) -> Result<(), InvalidRounds> {
+        struct Closure<'a, F: FnOnce(Fn1600)> {
+            rounds: usize,
+            f: F,
+            res: &'a mut Result<(), InvalidRounds>,
+        }
+
+        impl<F: FnOnce(Fn1600)> BackendClosure for Closure<'_, F> {
+            #[inline(always)]
+            fn call_once<B: Backend>(self) {
+                *self.res = B::get_p1600_rounds(self.rounds).map(self.f);
+            }
+        }
+
+        let mut res = Ok(());
+        self.with_backend(Closure {
+            rounds,
+            f,
+            res: &mut res,
+        });
+        res
+    }
 }
@@ -0,0 +1,221 @@
+//! TurboSHAKE extendable-output functions and KangarooTwelve hash functions
+//! defined in [RFC 9861].
+//!
+//! All functions are built on Keccak-p\[1600\] with 12 rounds. KangarooTwelve splits
+//! long inputs into chunks of 8192 bytes which form leaves of a Sakura-encoded tree.
+//! The leaves are hashed in batches using [`Backend::get_par_p1600`][crate::Backend::get_par_p1600]
+//! if the backend supports more than one parallel state.
+//!
+//! [RFC 9861]: https://www.rfc-editor.org/rfc/rfc9861
+use crate::{Keccak, State1600, leaves::Leaves, sakura::length_encode, sponge::Sponge};
+
+/// Number of rounds of Keccak-p\[1600\] used by the functions in this module.
+pub const ROUNDS: usize = 12;
+
+/// Default domain separation byte of TurboSHAKE.
+pub const DEFAULT_DOMAIN: u8 = 0x1F;
+
+/// Length of KangarooTwelve chunks in bytes.
+const CHUNK_LEN: usize = 8192;
+/// Domain separation byte of the single node tree.
+const SINGLE_NODE_DOMAIN: u8 = 0x07;
+/// Domain separation byte of the final node of a tree with leaves.
+const FINAL_NODE_DOMAIN: u8 = 0x06;
+/// Domain separation byte of the leaf nodes.
+const LEAF_DOMAIN: u8 = 0x0B;
+/// Sakura suffix appended to the first chunk in the final node (`110^62`).
+const FINAL_NODE_MARKER: [u8; 8] = [0x03, 0, 0, 0, 0, 0, 0, 0];
+/// Sakura suffix terminating the final node.
+const FINAL_NODE_TERMINATOR: [u8; 2] = [0xFF, 0xFF];
+
+macro_rules! impl_turbo_shake {
+    ($name:ident, $reader:ident, $fn_name:ident, $rate:expr, $alg:literal) => {
+        #[doc = concat!("The ", $alg, " extendable-output function.")]
+        #[derive(Clone, Debug)]
+        pub struct $name {
+            sponge: Sponge<State1600, $rate, ROUNDS>,
+            domain: u8,
+        }
+
+        impl $name {
+            /// Rate of the sponge in bytes.
+            pub const RATE: usize = $rate;
+
+            #[doc = concat!("Create new ", $alg, " hasher with the domain separation byte `domain`.")]
+            ///
+            /// # Panics
+            /// If `domain` is not in the `0x01..=0x7F` range.
+            #[must_use]
+            pub fn new(domain: u8) -> Self {
+                Self::new_with_keccak(Keccak::new(), domain)
+            }
+
+            #[doc = concat!("Create new ", $alg, " hasher using the provided [`Keccak`] instance.")]
+            ///
+            /// # Panics
+            /// If `domain` is not in the `0x01..=0x7F` range.
+            #[must_use]
+            pub fn new_with_keccak(keccak: Keccak, domain: u8) -> Self {
+                assert!(
+                    (0x01..=0x7F).contains(&domain),
+                    "domain separation byte must be in the 0x01..=0x7F range",
+                );
+                Self {
+                    sponge: Sponge::new_with_keccak(keccak),
+                    domain,
+                }
+            }
+
+            /// Absorb `data` into the hasher state.
+            pub fn update(&mut self, data: &[u8]) {
+                self.sponge.absorb(data);
+            }
+
+            /// Finalize the hasher and return reader of the output stream.
+            #[must_use]
+            pub fn finalize_xof(mut self) -> $reader {
+                self.sponge.absorb_last(self.domain);
+                $reader {
+                    sponge: self.sponge,
+                }
+            }
+        }
+
+        impl Default for $name {
+            #[inline]
+            fn default() -> Self {
+                Self::new(DEFAULT_DOMAIN)
+            }
+        }
+
+        #[doc = concat!("Reader of the ", $alg, " output stream.")]
+        #[derive(Clone, Debug)]
+        pub struct $reader {
+            sponge: Sponge<State1600, $rate, ROUNDS>,
+        }
+
+        impl $reader {
+            /// Read the next `out.len()` bytes of the output stream.
+            pub fn read(&mut self, out: &mut [u8]) {
+                self.sponge.squeeze(out);
+            }
+        }
+
+        #[doc = concat!("Compute ", $alg, " of `data` with the domain separation byte `domain`")]
+        /// and write `out.len()` bytes of output into `out`.
+        ///
+        /// # Panics
+        /// If `domain` is not in the `0x01..=0x7F` range.
+        pub fn $fn_name(data: &[u8], domain: u8, out: &mut [u8]) {
+            let mut hasher = $name::new(domain);
+            hasher.update(data);
+            hasher.finalize_xof().read(out);
+        }
+    };
+}
+
+impl_turbo_shake!(
+    TurboShake128,
+    TurboShake128Reader,
+    turbo_shake128,
+    168,
+    "TurboSHAKE128"
+);
+impl_turbo_shake!(
+    TurboShake256,
+    TurboShake256Reader,
+    turbo_shake256,
+    136,
+    "TurboSHAKE256"
+);
+
+macro_rules! impl_kt {
+    (
+        $name:ident, $turbo_reader:ident, $fn_name:ident, $rate:expr, $cv_len:expr,
+        $alg:literal
+    ) => {
+        #[doc = concat!("The ", $alg, " hash function.")]
+        #[derive(Clone, Debug)]
+        pub struct $name {
+            node: Sponge<State1600, $rate, ROUNDS>,
+            first_chunk_len: usize,
+            tree: bool,
+            leaves: Leaves<$rate, ROUNDS, $cv_len>,
+        }
+
+        impl $name {
+            #[doc = concat!("Create new ", $alg, " hasher.")]
+            #[must_use]
+            pub fn new() -> Self {
+                Self::new_with_keccak(Keccak::new())
+            }
+
+            #[doc = concat!("Create new ", $alg, " hasher using the provided [`Keccak`] instance.")]
+            #[must_use]
+            pub fn new_with_keccak(keccak: Keccak) -> Self {
+                Self {
+                    node: Sponge::new_with_keccak(keccak),
+                    first_chunk_len: 0,
+                    tree: false,
+                    leaves: Leaves::new(keccak, CHUNK_LEN, LEAF_DOMAIN),
+                }
+            }
+
+            /// Absorb `data` into the hasher state.
+            pub fn update(&mut self, mut data: &[u8]) {
+                if !self.tree {
+                    let n = (CHUNK_LEN - self.first_chunk_len).min(data.len());
+                    self.node.absorb(&data[..n]);
+                    self.first_chunk_len += n;
+                    data = &data[n..];
+                    if data.is_empty() {
+                        return;
+                    }
+                    self.node.absorb(&FINAL_NODE_MARKER);
+                    self.tree = true;
+                }
+
+                let node = &mut self.node;
+                self.leaves.update(data, |cv| node.absorb(cv));
+            }
+
+            /// Finalize the hasher with the customization string `customization`
+            /// and return reader of the output stream.
+            #[must_use]
+            pub fn finalize_xof(mut self, customization: &[u8]) -> $turbo_reader {
+                self.update(customization);
+                length_encode(customization.len() as u64, |data| self.update(data));
+
+                let domain = if self.tree {
+                    let node = &mut self.node;
+                    self.leaves.finish(|cv| node.absorb(cv));
+                    length_encode(self.leaves.count(), |data| self.node.absorb(data));
+                    self.node.absorb(&FINAL_NODE_TERMINATOR);
+                    FINAL_NODE_DOMAIN
+                } else {
+                    SINGLE_NODE_DOMAIN
+                };
+                self.node.absorb_last(domain);
+                $turbo_reader { sponge: self.node }
+            }
+        }
+
+        impl Default for $name {
+            #[inline]
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        #[doc = concat!("Compute ", $alg, " of `data` with the customization string `customization`")]
+        /// and write `out.len()` bytes of output into `out`.
+        pub fn $fn_name(data: &[u8], customization: &[u8], out: &mut [u8]) {
+            let mut hasher = $name::new();
+            hasher.update(data);
+            hasher.finalize_xof(customization).read(out);
+        }
+    };
+}
+
+impl_kt!(Kt128, TurboShake128Reader, kt128, 168, 32, "KT128");
+impl_kt!(Kt256, TurboShake256Reader, kt256, 136, 64, "KT256");
@@ -0,0 +1,274 @@
+//! Generic tree hashing with the [Sakura] coding over Keccak-p\[1600\].
+//!
+//! Message is split into leaves of a fixed length. Chaining values of the leaves are
+//! combined by levels of inner nodes with a fixed fan-out, and the last level is combined
+//! by the final node whose output is the hash. Messages which fit into a single leaf
+//! are hashed as a single final node.
+//!
+//! Nodes are coded as follows (frame bits are written in the order of absorption and
+//! followed by the `pad10*1` padding):
+//!
+//! - leaf: `leaf || 110` (suffix `0x0B`);
+//! - inner node: `CV_0 || ... || CV_(k-1) || length_encode(k) || FF FF || 010` (suffix `0x0A`);
+//! - final node: `CV_0 || ... || CV_(k-1) || length_encode(k) || FF FF || 01` (suffix `0x06`);
+//! - single final node: `message || 11` (suffix `0x07`).
+//!
+//! With the 12-round permutation and the rate of 168 bytes, leaves and single final nodes
+//! are compatible with the corresponding nodes of KangarooTwelve.
+//!
+//! [Sakura]: https://keccak.team/files/Sakura.pdf
+use crate::{Keccak, State1600, leaves::Leaves, sponge::Sponge};
+
+/// Maximum supported tree depth.
+pub const MAX_DEPTH: usize = 8;
+
+/// Suffix of leaves: message hop `1` followed by inner node frame bits `10`.
+const LEAF_SUFFIX: u8 = 0x0B;
+/// Suffix of inner nodes: chaining hop `0` followed by inner node frame bits `10`.
+const INNER_SUFFIX: u8 = 0x0A;
+/// Suffix of the final node: chaining hop `0` followed by final node frame bit `1`.
+const FINAL_SUFFIX: u8 = 0x06;
+/// Suffix of the single final node: message hop `1` followed by final node frame bit `1`.
+const SINGLE_NODE_SUFFIX: u8 = 0x07;
+/// Coded interleaving block size of chaining hops (no interleaving).
+const NO_INTERLEAVING: [u8; 2] = [0xFF, 0xFF];
+
+/// Encode `x` as a big-endian byte string without leading zeros followed by its length
+/// and pass the result into `sink`.
+///
+/// This is the coding of the number of chaining values in Sakura chaining hops.
+#[allow(clippy::cast_possible_truncation)]
+pub fn length_encode(x: u64, mut sink: impl FnMut(&[u8])) {
+    let n = (64 - x.leading_zeros() as usize).div_ceil(8);
+    let mut buf = [0u8; 9];
+    buf[..8].copy_from_slice(&x.to_be_bytes());
+    buf[8] = n as u8;
+    sink(&buf[8 - n..]);
+}
+
+/// Shape of a Sakura tree.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TreeParams {
+    leaf_len: usize,
+    fan_out: usize,
+    depth: usize,
+}
+
+impl TreeParams {
+    /// Create tree parameters.
+    ///
+    /// - `leaf_len`: length of message leaves in bytes.
+    /// - `fan_out`: maximum number of chaining values combined by an inner node.
+    /// - `depth`: number of node levels including the leaves and the final node.
+    ///   With `depth` equal to 2 the final node combines all leaves and `fan_out`
+    ///   is not used.
+    ///
+    /// # Panics
+    /// If `leaf_len` is equal to zero, if `fan_out` is smaller than 2,
+    /// or if `depth` is not in the `2..=MAX_DEPTH` range.
+    #[must_use]
+    pub const fn new(leaf_len: usize, fan_out: usize, depth: usize) -> Self {
+        assert!(leaf_len != 0, "leaf length must not be zero");
+        assert!(fan_out >= 2, "fan-out must be at least 2");
+        assert!(
+            depth >= 2 && depth <= MAX_DEPTH,
+            "tree depth must be in the 2..=MAX_DEPTH range",
+        );
+        Self {
+            leaf_len,
+            fan_out,
+            depth,
+        }
+    }
+
+    /// Length of message leaves in bytes.
+    #[must_use]
+    pub const fn leaf_len(&self) -> usize {
+        self.leaf_len
+    }
+
+    /// Maximum number of chaining values combined by an inner node.
+    #[must_use]
+    pub const fn fan_out(&self) -> usize {
+        self.fan_out
+    }
+
+    /// Number of node levels including the leaves and the final node.
+    #[must_use]
+    pub const fn depth(&self) -> usize {
+        self.depth
+    }
+}
+
+/// Node which accumulates chaining values.
+#[derive(Clone, Debug)]
+struct ChainingNode<const RATE: usize, const ROUNDS: usize> {
+    sponge: Sponge<State1600, RATE, ROUNDS>,
+    cv_count: u64,
+}
+
+impl<const RATE: usize, const ROUNDS: usize> ChainingNode<RATE, ROUNDS> {
+    fn new(keccak: Keccak) -> Self {
+        Self {
+            sponge: Sponge::new_with_keccak(keccak),
+            cv_count: 0,
+        }
+    }
+
+    fn absorb_cv(&mut self, cv: &[u8]) {
+        self.sponge.absorb(cv);
+        self.cv_count += 1;
+    }
+
+    /// Absorb the end of the chaining hop followed by `suffix`.
+    fn finish(&mut self, suffix: u8) {
+        length_encode(self.cv_count, |data| self.sponge.absorb(data));
+        self.sponge.absorb(&NO_INTERLEAVING);
+        self.sponge.absorb_last(suffix);
+    }
+}
+
+/// Inner node levels and the final node of a tree.
+#[derive(Clone, Debug)]
+struct Nodes<const RATE: usize, const ROUNDS: usize, const CV_LEN: usize> {
+    keccak: Keccak,
+    params: TreeParams,
+    inner: [ChainingNode<RATE, ROUNDS>; MAX_DEPTH - 2],
+    last: ChainingNode<RATE, ROUNDS>,
+}
+
+impl<const RATE: usize, const ROUNDS: usize, const CV_LEN: usize> Nodes<RATE, ROUNDS, CV_LEN> {
+    /// Number of inner node levels.
+    fn inner_levels(&self) -> usize {
+        self.params.depth - 2
+    }
+
+    /// Add chaining value `cv` to the inner node level `level` (or to the final node
+    /// if `level` is equal to the number of inner levels).
+    fn push(&mut self, level: usize, cv: &[u8]) {
+        if level == self.inner_levels() {
+            self.last.absorb_cv(cv);
+            return;
+        }
+        let node = &mut self.inner[level];
+        node.absorb_cv(cv);
+        if node.cv_count == self.params.fan_out as u64 {
+            self.close(level);
+        }
+    }
+
+    /// Finish the current node on the inner level `level` and push its chaining value
+    /// to the next level.
+    fn close(&mut self, level: usize) {
+        let mut cv = [0u8; CV_LEN];
+        let node = &mut self.inner[level];
+        node.finish(INNER_SUFFIX);
+        node.sponge.squeeze(&mut cv);
+        *node = ChainingNode::new(self.keccak);
+        self.push(level + 1, &cv);
+    }
+}
+
+/// Incremental Sakura tree hasher over Keccak-p\[1600\] with `ROUNDS` rounds,
+/// rate of `RATE` bytes, and chaining values of `CV_LEN` bytes.
+///
+/// The tree shape is defined by [`TreeParams`]. Whole leaves passed to a single
+/// [`update`][Self::update] call are hashed in batches using
+/// [`Backend::get_par_p1600`][crate::Backend::get_par_p1600] if the backend supports
+/// more than one parallel state.
+///
+/// # Panics
+/// Construction fails at compile time if `RATE` is not a multiple of 8 or if `CV_LEN`
+/// is bigger than `RATE`.
+#[derive(Clone, Debug)]
+pub struct TreeHash<const RATE: usize, const ROUNDS: usize, const CV_LEN: usize> {
+    first_leaf: Sponge<State1600, RATE, ROUNDS>,
+    first_leaf_len: usize,
+    tree: bool,
+    leaves: Leaves<RATE, ROUNDS, CV_LEN>,
+    nodes: Nodes<RATE, ROUNDS, CV_LEN>,
+}
+
+impl<const RATE: usize, const ROUNDS: usize, const CV_LEN: usize> TreeHash<RATE, ROUNDS, CV_LEN> {
+    /// Create new tree hasher with the tree shape `params`.
+    #[must_use]
+    pub fn new(params: TreeParams) -> Self {
+        Self::new_with_keccak(Keccak::new(), params)
+    }
+
+    /// Create new tree hasher using the provided [`Keccak`] instance.
+    #[must_use]
+    pub fn new_with_keccak(keccak: Keccak, params: TreeParams) -> Self {
+        Self {
+            first_leaf: Sponge::new_with_keccak(keccak),
+            first_leaf_len: 0,
+            tree: false,
+            leaves: Leaves::new(keccak, params.leaf_len, LEAF_SUFFIX),
+            nodes: Nodes {
+                keccak,
+                params,
+                inner: core::array::from_fn(|_| ChainingNode::new(keccak)),
+                last: ChainingNode::new(keccak),
+            },
+        }
+    }
+
+    /// Absorb `data` into the hasher state.
+    pub fn update(&mut self, mut data: &[u8]) {
+        if !self.tree {
+            let leaf_len = self.nodes.params.leaf_len;
+            let n = (leaf_len - self.first_leaf_len).min(data.len());
+            self.first_leaf.absorb(&data[..n]);
+            self.first_leaf_len += n;
+            data = &data[n..];
+            if data.is_empty() {
+                return;
+            }
+
+            let mut cv = [0u8; CV_LEN];
+            self.first_leaf.absorb_last(LEAF_SUFFIX);
+            self.first_leaf.squeeze(&mut cv);
+            self.nodes.push(0, &cv);
+            self.tree = true;
+        }
+
+        let nodes = &mut self.nodes;
+        self.leaves.update(data, |cv| nodes.push(0, cv));
+    }
+
+    /// Finalize the hasher and return reader of the output stream of the final node.
+    #[must_use]
+    pub fn finalize_xof(mut self) -> TreeReader<RATE, ROUNDS> {
+        if !self.tree {
+            self.first_leaf.absorb_last(SINGLE_NODE_SUFFIX);
+            return TreeReader {
+                sponge: self.first_leaf,
+            };
+        }
+
+        let nodes = &mut self.nodes;
+        self.leaves.finish(|cv| nodes.push(0, cv));
+        for level in 0..nodes.inner_levels() {
+            if nodes.inner[level].cv_count != 0 {
+                nodes.close(level);
+            }
+        }
+        nodes.last.finish(FINAL_SUFFIX);
+        TreeReader {
+            sponge: self.nodes.last.sponge,
+        }
+    }
+}
+
+/// Reader of the output stream of a Sakura tree hash.
+#[derive(Clone, Debug)]
+pub struct TreeReader<const RATE: usize, const ROUNDS: usize> {
+    sponge: Sponge<State1600, RATE, ROUNDS>,
+}
+
+impl<const RATE: usize, const ROUNDS: usize> TreeReader<RATE, ROUNDS> {
+    /// Read the next `out.len()` bytes of the output stream.
+    pub fn read(&mut self, out: &mut [u8]) {
+        self.sponge.squeeze(out);
+    }
+}
@@ -0,0 +1,515 @@
+//! cSHAKE, KMAC, TupleHash, and ParallelHash functions defined in [NIST SP 800-185].
+//!
+//! [NIST SP 800-185]: https://nvlpubs.nist.gov/nistpubs/SpecialPublications/NIST.SP.800-185.pdf
+use crate::consts::F1600_ROUNDS;
+use crate::{Keccak, State1600, leaves::Leaves, sponge::Sponge};
+use core::{fmt, ops::Deref};
+
+/// Delimited suffix of cSHAKE (`00` followed by the padding bit).
+const CSHAKE_SUFFIX: u8 = 0x04;
+/// Delimited suffix of SHAKE (`1111` followed by the padding bit).
+const SHAKE_SUFFIX: u8 = 0x1F;
+
+/// Integer encoded by [`left_encode`] or [`right_encode`].
+#[derive(Clone, Copy)]
+pub struct EncodedInt {
+    buf: [u8; 9],
+    start: usize,
+    end: usize,
+}
+
+impl EncodedInt {
+    /// Get the encoding as a byte slice.
+    ///
+    /// Unlike the [`Deref`] implementation, this method can be used in const contexts.
+    #[must_use]
+    pub const fn as_bytes(&self) -> &[u8] {
+        self.buf
+            .split_at(self.start)
+            .1
+            .split_at(self.end - self.start)
+            .0
+    }
+}
+
+impl Deref for EncodedInt {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl AsRef<[u8]> for EncodedInt {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+impl fmt::Debug for EncodedInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("EncodedInt").field(&self.deref()).finish()
+    }
+}
+
+/// Number of bytes in the big-endian representation of `x` without leading zeros
+/// (at least one).
+#[inline]
+const fn byte_len(x: u64) -> usize {
+    let n = (64 - x.leading_zeros() as usize).div_ceil(8);
+    if n == 0 { 1 } else { n }
+}
+
+/// Encode `x` as a byte string prefixed by its length.
+#[must_use]
+#[allow(clippy::cast_possible_truncation)]
+pub const fn left_encode(x: u64) -> EncodedInt {
+    let n = byte_len(x);
+    let [b0, b1, b2, b3, b4, b5, b6, b7] = x.to_be_bytes();
+    let mut buf = [0, b0, b1, b2, b3, b4, b5, b6, b7];
+    buf[8 - n] = n as u8;
+    EncodedInt {
+        buf,
+        start: 8 - n,
+        end: 9,
+    }
+}
+
+/// Encode `x` as a byte string followed by its length.
+#[must_use]
+#[allow(clippy::cast_possible_truncation)]
+pub const fn right_encode(x: u64) -> EncodedInt {
+    let n = byte_len(x);
+    let [b0, b1, b2, b3, b4, b5, b6, b7] = x.to_be_bytes();
+    let buf = [b0, b1, b2, b3, b4, b5, b6, b7, n as u8];
+    EncodedInt {
+        buf,
+        start: 8 - n,
+        end: 9,
+    }
+}
+
+/// Bit length of a byte string with length `len`.
+///
+/// Saturates at `u64::MAX`, which is reachable only by lengths of at least 2^61 bytes.
+/// Byte strings of such lengths can not be stored in memory of existing machines.
+#[inline]
+pub(crate) const fn bit_len(len: usize) -> u64 {
+    (len as u64).saturating_mul(8)
+}
+
+/// Pass the `encode_string(s)` encoding of `s` into `sink`.
+pub fn encode_string(s: &[u8], mut sink: impl FnMut(&[u8])) {
+    sink(&left_encode(bit_len(s.len())));
+    sink(s);
+}
+
+/// Pass the `bytepad(X, w)` encoding into `sink`, where `X` is the concatenation
+/// of all byte strings passed by `x` into its argument.
+///
+/// # Panics
+/// If `w` is equal to zero.
+pub fn bytepad(w: usize, x: impl FnOnce(&mut dyn FnMut(&[u8])), mut sink: impl FnMut(&[u8])) {
+    assert_ne!(w, 0, "bytepad width must not be zero");
+
+    let prefix = left_encode(w as u64);
+    sink(&prefix);
+    let mut len = prefix.len();
+    x(&mut |data| {
+        len += data.len();
+        sink(data);
+    });
+
+    let mut pad_len = (w - len % w) % w;
+    while pad_len != 0 {
+        let n = pad_len.min(64);
+        sink(&[0u8; 64][..n]);
+        pad_len -= n;
+    }
+}
+
+macro_rules! impl_cshake {
+    ($name:ident, $reader:ident, $fn_name:ident, $rate:expr, $alg:literal) => {
+        #[doc = concat!("The ", $alg, " customizable extendable-output function.")]
+        #[derive(Clone, Debug)]
+        pub struct $name {
+            sponge: Sponge<State1600, $rate, F1600_ROUNDS>,
+            suffix: u8,
+        }
+
+        impl $name {
+            /// Rate of the sponge in bytes.
+            pub const RATE: usize = $rate;
+
+            #[doc = concat!("Create new ", $alg, " hasher with the function name `function_name`")]
+            /// and the customization string `customization`.
+            ///
+            /// If both strings are empty, the function is equivalent to SHAKE.
+            #[must_use]
+            pub fn new(function_name: &[u8], customization: &[u8]) -> Self {
+                Self::new_with_keccak(Keccak::new(), function_name, customization)
+            }
+
+            #[doc = concat!("Create new ", $alg, " hasher using the provided [`Keccak`] instance.")]
+            #[must_use]
+            pub fn new_with_keccak(
+                keccak: Keccak,
+                function_name: &[u8],
+                customization: &[u8],
+            ) -> Self {
+                let mut sponge = Sponge::new_with_keccak(keccak);
+                if function_name.is_empty() && customization.is_empty() {
+                    return Self {
+                        sponge,
+                        suffix: SHAKE_SUFFIX,
+                    };
+                }
+
+                bytepad(
+                    $rate,
+                    |sink| {
+                        encode_string(function_name, &mut *sink);
+                        encode_string(customization, sink);
+                    },
+                    |data| sponge.absorb(data),
+                );
+                Self {
+                    sponge,
+                    suffix: CSHAKE_SUFFIX,
+                }
+            }
+
+            /// Absorb `data` into the hasher state.
+            pub fn update(&mut self, data: &[u8]) {
+                self.sponge.absorb(data);
+            }
+
+            /// Finalize the hasher and return reader of the output stream.
+            #[must_use]
+            pub fn finalize_xof(mut self) -> $reader {
+                self.sponge.absorb_last(self.suffix);
+                $reader {
+                    sponge: self.sponge,
+                }
+            }
+        }
+
+        #[doc = concat!("Reader of the ", $alg, " output stream.")]
+        #[derive(Clone, Debug)]
+        pub struct $reader {
+            sponge: Sponge<State1600, $rate, F1600_ROUNDS>,
+        }
+
+        impl $reader {
+            /// Read the next `out.len()` bytes of the output stream.
+            pub fn read(&mut self, out: &mut [u8]) {
+                self.sponge.squeeze(out);
+            }
+        }
+
+        #[doc = concat!("Compute ", $alg, " of `data` and write `out.len()` bytes of output into `out`.")]
+        pub fn $fn_name(data: &[u8], function_name: &[u8], customization: &[u8], out: &mut [u8]) {
+            let mut hasher = $name::new(function_name, customization);
+            hasher.update(data);
+            hasher.finalize_xof().read(out);
+        }
+    };
+}
+
+impl_cshake!(CShake128, CShake128Reader, cshake128, 168, "cSHAKE128");
+impl_cshake!(CShake256, CShake256Reader, cshake256, 136, "cSHAKE256");
+
+macro_rules! impl_kmac {
+    (
+        $name:ident, $cshake:ident, $reader:ident, $fn_name:ident, $xof_fn_name:ident,
+        $alg:literal
+    ) => {
+        #[doc = concat!("The ", $alg, " message authentication code.")]
+        #[derive(Clone, Debug)]
+        pub struct $name {
+            cshake: $cshake,
+        }
+
+        impl $name {
+            #[doc = concat!("Create new ", $alg, " instance with the key `key`")]
+            /// and the customization string `customization`.
+            #[must_use]
+            pub fn new(key: &[u8], customization: &[u8]) -> Self {
+                Self::new_with_keccak(Keccak::new(), key, customization)
+            }
+
+            #[doc = concat!("Create new ", $alg, " instance using the provided [`Keccak`] instance.")]
+            #[must_use]
+            pub fn new_with_keccak(keccak: Keccak, key: &[u8], customization: &[u8]) -> Self {
+                let mut cshake = $cshake::new_with_keccak(keccak, b"KMAC", customization);
+                bytepad($cshake::RATE, |sink| encode_string(key, sink), |data| {
+                    cshake.update(data);
+                });
+                Self { cshake }
+            }
+
+            /// Absorb `data` into the MAC state.
+            pub fn update(&mut self, data: &[u8]) {
+                self.cshake.update(data);
+            }
+
+            /// Finalize the MAC and write `out.len()` bytes of the tag into `out`.
+            ///
+            /// The output length is bound to the tag, i.e. tags of different lengths
+            /// are unrelated.
+            pub fn finalize(mut self, out: &mut [u8]) {
+                self.cshake.update(&right_encode(bit_len(out.len())));
+                self.cshake.finalize_xof().read(out);
+            }
+
+            #[doc = concat!("Finalize the MAC in the ", $alg, "XOF mode and return reader")]
+            /// of the output stream.
+            #[must_use]
+            pub fn finalize_xof(mut self) -> $reader {
+                self.cshake.update(&right_encode(0));
+                self.cshake.finalize_xof()
+            }
+        }
+
+        #[doc = concat!("Compute ", $alg, " of `data` and write `out.len()` bytes of the tag into `out`.")]
+        pub fn $fn_name(key: &[u8], data: &[u8], customization: &[u8], out: &mut [u8]) {
+            let mut mac = $name::new(key, customization);
+            mac.update(data);
+            mac.finalize(out);
+        }
+
+        #[doc = concat!("Compute ", $alg, "XOF of `data` and write `out.len()` bytes of output into `out`.")]
+        pub fn $xof_fn_name(key: &[u8], data: &[u8], customization: &[u8], out: &mut [u8]) {
+            let mut mac = $name::new(key, customization);
+            mac.update(data);
+            mac.finalize_xof().read(out);
+        }
+    };
+}
+
+impl_kmac!(
+    Kmac128,
+    CShake128,
+    CShake128Reader,
+    kmac128,
+    kmac_xof128,
+    "KMAC128"
+);
+impl_kmac!(
+    Kmac256,
+    CShake256,
+    CShake256Reader,
+    kmac256,
+    kmac_xof256,
+    "KMAC256"
+);
+
+macro_rules! impl_tuple_hash {
+    (
+        $name:ident, $cshake:ident, $reader:ident, $fn_name:ident, $xof_fn_name:ident,
+        $alg:literal
+    ) => {
+        #[doc = concat!("The ", $alg, " hash function over tuples of byte strings.")]
+        #[derive(Clone, Debug)]
+        pub struct $name {
+            cshake: $cshake,
+        }
+
+        impl $name {
+            #[doc = concat!("Create new ", $alg, " hasher with the customization string `customization`.")]
+            #[must_use]
+            pub fn new(customization: &[u8]) -> Self {
+                Self::new_with_keccak(Keccak::new(), customization)
+            }
+
+            #[doc = concat!("Create new ", $alg, " hasher using the provided [`Keccak`] instance.")]
+            #[must_use]
+            pub fn new_with_keccak(keccak: Keccak, customization: &[u8]) -> Self {
+                Self {
+                    cshake: $cshake::new_with_keccak(keccak, b"TupleHash", customization),
+                }
+            }
+
+            /// Absorb `item` as the next element of the tuple.
+            pub fn update(&mut self, item: &[u8]) {
+                encode_string(item, |data| self.cshake.update(data));
+            }
+
+            /// Finalize the hasher and write `out.len()` bytes of the hash into `out`.
+            ///
+            /// The output length is bound to the hash, i.e. hashes of different lengths
+            /// are unrelated.
+            pub fn finalize(mut self, out: &mut [u8]) {
+                self.cshake.update(&right_encode(bit_len(out.len())));
+                self.cshake.finalize_xof().read(out);
+            }
+
+            #[doc = concat!("Finalize the hasher in the ", $alg, "XOF mode and return reader")]
+            /// of the output stream.
+            #[must_use]
+            pub fn finalize_xof(mut self) -> $reader {
+                self.cshake.update(&right_encode(0));
+                self.cshake.finalize_xof()
+            }
+        }
+
+        #[doc = concat!("Compute ", $alg, " of the tuple `items` and write `out.len()` bytes")]
+        /// of the hash into `out`.
+        pub fn $fn_name(items: &[&[u8]], customization: &[u8], out: &mut [u8]) {
+            let mut hasher = $name::new(customization);
+            for item in items {
+                hasher.update(item);
+            }
+            hasher.finalize(out);
+        }
+
+        #[doc = concat!("Compute ", $alg, "XOF of the tuple `items` and write `out.len()` bytes")]
+        /// of output into `out`.
+        pub fn $xof_fn_name(items: &[&[u8]], customization: &[u8], out: &mut [u8]) {
+            let mut hasher = $name::new(customization);
+            for item in items {
+                hasher.update(item);
+            }
+            hasher.finalize_xof().read(out);
+        }
+    };
+}
+
+impl_tuple_hash!(
+    TupleHash128,
+    CShake128,
+    CShake128Reader,
+    tuple_hash128,
+    tuple_hash_xof128,
+    "TupleHash128"
+);
+impl_tuple_hash!(
+    TupleHash256,
+    CShake256,
+    CShake256Reader,
+    tuple_hash256,
+    tuple_hash_xof256,
+    "TupleHash256"
+);
+
+macro_rules! impl_parallel_hash {
+    (
+        $name:ident, $cshake:ident, $reader:ident, $fn_name:ident, $xof_fn_name:ident,
+        $rate:expr, $cv_len:expr, $alg:literal
+    ) => {
+        #[doc = concat!("The ", $alg, " hash function.")]
+        ///
+        /// Input is split into blocks of `block_size` bytes which are hashed independently.
+        /// Whole blocks passed to a single [`update`][Self::update] call are hashed in batches
+        /// using the parallel Keccak-f1600 function of the selected backend.
+        #[derive(Clone, Debug)]
+        pub struct $name {
+            cshake: $cshake,
+            leaves: Leaves<$rate, F1600_ROUNDS, $cv_len>,
+        }
+
+        impl $name {
+            #[doc = concat!("Create new ", $alg, " hasher with the block size of `block_size` bytes")]
+            /// and the customization string `customization`.
+            ///
+            /// # Panics
+            /// If `block_size` is equal to zero.
+            #[must_use]
+            pub fn new(block_size: usize, customization: &[u8]) -> Self {
+                Self::new_with_keccak(Keccak::new(), block_size, customization)
+            }
+
+            #[doc = concat!("Create new ", $alg, " hasher using the provided [`Keccak`] instance.")]
+            ///
+            /// # Panics
+            /// If `block_size` is equal to zero.
+            #[must_use]
+            pub fn new_with_keccak(keccak: Keccak, block_size: usize, customization: &[u8]) -> Self {
+                assert_ne!(block_size, 0, "block size must not be zero");
+                let mut cshake = $cshake::new_with_keccak(keccak, b"ParallelHash", customization);
+                cshake.update(&left_encode(block_size as u64));
+                Self {
+                    cshake,
+                    leaves: Leaves::new(keccak, block_size, SHAKE_SUFFIX),
+                }
+            }
+
+            /// Absorb `data` into the hasher state.
+            pub fn update(&mut self, data: &[u8]) {
+                let cshake = &mut self.cshake;
+                self.leaves.update(data, |cv| cshake.update(cv));
+            }
+
+            /// Finalize the hasher and write `out.len()` bytes of the hash into `out`.
+            ///
+            /// The output length is bound to the hash, i.e. hashes of different lengths
+            /// are unrelated.
+            pub fn finalize(mut self, out: &mut [u8]) {
+                self.finish_last_leaf(bit_len(out.len()));
+                self.cshake.finalize_xof().read(out);
+            }
+
+            #[doc = concat!("Finalize the hasher in the ", $alg, "XOF mode and return reader")]
+            /// of the output stream.
+            #[must_use]
+            pub fn finalize_xof(mut self) -> $reader {
+                self.finish_last_leaf(0);
+                self.cshake.finalize_xof()
+            }
+
+            /// Finish the last partial leaf (if any) and absorb the encoded number
+            /// of leaves followed by the encoded output length `out_bits`.
+            fn finish_last_leaf(&mut self, out_bits: u64) {
+                let cshake = &mut self.cshake;
+                self.leaves.finish(|cv| cshake.update(cv));
+                self.cshake.update(&right_encode(self.leaves.count()));
+                self.cshake.update(&right_encode(out_bits));
+            }
+        }
+
+        #[doc = concat!("Compute ", $alg, " of `data` with the block size of `block_size` bytes")]
+        /// and write `out.len()` bytes of the hash into `out`.
+        ///
+        /// # Panics
+        /// If `block_size` is equal to zero.
+        pub fn $fn_name(data: &[u8], block_size: usize, customization: &[u8], out: &mut [u8]) {
+            let mut hasher = $name::new(block_size, customization);
+            hasher.update(data);
+            hasher.finalize(out);
+        }
+
+        #[doc = concat!("Compute ", $alg, "XOF of `data` with the block size of `block_size` bytes")]
+        /// and write `out.len()` bytes of output into `out`.
+        ///
+        /// # Panics
+        /// If `block_size` is equal to zero.
+        pub fn $xof_fn_name(data: &[u8], block_size: usize, customization: &[u8], out: &mut [u8]) {
+            let mut hasher = $name::new(block_size, customization);
+            hasher.update(data);
+            hasher.finalize_xof().read(out);
+        }
+    };
+}
+
+impl_parallel_hash!(
+    ParallelHash128,
+    CShake128,
+    CShake128Reader,
+    parallel_hash128,
+    parallel_hash_xof128,
+    168,
+    32,
+    "ParallelHash128"
+);
+impl_parallel_hash!(
+    ParallelHash256,
+    CShake256,
+    CShake256Reader,
+    parallel_hash256,
+    parallel_hash_xof256,
+    136,
+    64,
+    "ParallelHash256"
+);
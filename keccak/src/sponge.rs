@@ -0,0 +1,379 @@
+//! Generic Keccak sponge construction.
+//!
+//! [`Sponge`] implements `Keccak[r, c]` over any of the Keccak-p permutation widths
+//! with the multi-rate `pad10*1` padding.
+use crate::generic::LaneSize;
+use crate::{Backend, BackendClosure, Keccak, State200, State400, State800, State1600, StateBytes};
+use core::fmt;
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Keccak permutation width usable with [`Sponge`].
+///
+/// This trait is sealed and implemented only for [`State200`], [`State400`],
+/// [`State800`], and [`State1600`].
+pub trait Width: StateBytes + Copy + Default + sealed::Sealed {
+    /// Get `p` function with the specified number of rounds for this width.
+    #[must_use]
+    fn get_p<const ROUNDS: usize>(keccak: &Keccak) -> fn(&mut Self);
+
+    /// Get byte-oriented state access functions of the backend used by `keccak`.
+    #[doc(hidden)]
+    #[must_use]
+    fn get_snp(keccak: &Keccak) -> Snp<Self>;
+
+    /// Absorb full blocks of `data` with the specified `rate` in bytes.
+    ///
+    /// Returns number of absorbed bytes.
+    #[inline]
+    #[doc(hidden)]
+    fn absorb_blocks<const ROUNDS: usize>(
+        keccak: &Keccak,
+        p: fn(&mut Self),
+        state: &mut Self,
+        rate: usize,
+        data: &[u8],
+    ) -> usize {
+        absorb_blocks_generic(Self::get_snp(keccak).add_bytes, p, state, rate, data)
+    }
+}
+
+/// Byte-oriented state access functions ("SnP" in XKCP terms) of a [`Backend`].
+#[doc(hidden)]
+#[derive(Clone, Copy, Debug)]
+pub struct Snp<W> {
+    pub add_bytes: fn(&mut W, &[u8], usize),
+    pub overwrite_bytes: fn(&mut W, &[u8], usize),
+    pub overwrite_with_zeroes: fn(&mut W, usize),
+    pub extract_bytes: fn(&W, &mut [u8], usize),
+}
+
+/// Implement [`Width::get_snp`] using the SnP methods of [`Backend`].
+macro_rules! get_snp {
+    ($state:ty, $add:ident, $overwrite:ident, $zeroes:ident, $extract:ident) => {
+        #[inline]
+        fn get_snp(keccak: &Keccak) -> Snp<Self> {
+            struct Closure<'a>(&'a mut Option<Snp<$state>>);
+
+            impl BackendClosure for Closure<'_> {
+                #[inline(always)]
+                fn call_once<B: Backend>(self) {
+                    *self.0 = Some(Snp {
+                        add_bytes: B::$add,
+                        overwrite_bytes: B::$overwrite,
+                        overwrite_with_zeroes: B::$zeroes,
+                        extract_bytes: B::$extract,
+                    });
+                }
+            }
+
+            let mut res = None;
+            keccak.with_backend(Closure(&mut res));
+            res.expect("backend closure is always called")
+        }
+    };
+}
+
+/// Absorb full blocks of `data` by applying the permutation function `p` after every block.
+#[inline(always)]
+fn absorb_blocks_generic<W>(
+    add_bytes: fn(&mut W, &[u8], usize),
+    p: fn(&mut W),
+    state: &mut W,
+    rate: usize,
+    data: &[u8],
+) -> usize {
+    let mut blocks = data.chunks_exact(rate);
+    for block in &mut blocks {
+        add_bytes(state, block, 0);
+        p(state);
+    }
+    data.len() - blocks.remainder().len()
+}
+
+macro_rules! impl_width {
+    (
+        $state:ty, $lane:ty, $add_bytes:ident, $overwrite_bytes:ident,
+        $overwrite_with_zeroes:ident, $extract_bytes:ident
+    ) => {
+        impl sealed::Sealed for $state {}
+
+        impl Width for $state {
+            #[inline]
+            fn get_p<const ROUNDS: usize>(keccak: &Keccak) -> fn(&mut Self) {
+                <$lane as LaneSize>::get_p::<ROUNDS>(keccak)
+            }
+
+            get_snp!(
+                $state,
+                $add_bytes,
+                $overwrite_bytes,
+                $overwrite_with_zeroes,
+                $extract_bytes
+            );
+
+            #[inline]
+            fn absorb_blocks<const ROUNDS: usize>(
+                keccak: &Keccak,
+                p: fn(&mut Self),
+                state: &mut Self,
+                rate: usize,
+                data: &[u8],
+            ) -> usize {
+                struct Closure<'a> {
+                    p: fn(&mut $state),
+                    state: &'a mut $state,
+                    rate: usize,
+                    data: &'a [u8],
+                    res: &'a mut usize,
+                }
+
+                impl BackendClosure for Closure<'_> {
+                    #[inline(always)]
+                    fn call_once<B: Backend>(self) {
+                        let mut blocks = self.data.chunks_exact(self.rate);
+                        for block in &mut blocks {
+                            B::$add_bytes(self.state, block, 0);
+                            (self.p)(self.state);
+                        }
+                        *self.res = self.data.len() - blocks.remainder().len();
+                    }
+                }
+
+                let mut res = 0;
+                keccak.with_backend(Closure {
+                    p,
+                    state,
+                    rate,
+                    data,
+                    res: &mut res,
+                });
+                res
+            }
+        }
+    };
+}
+
+impl_width!(
+    State200,
+    u8,
+    add_bytes200,
+    overwrite_bytes200,
+    overwrite_with_zeroes200,
+    extract_bytes200
+);
+impl_width!(
+    State400,
+    u16,
+    add_bytes400,
+    overwrite_bytes400,
+    overwrite_with_zeroes400,
+    extract_bytes400
+);
+impl_width!(
+    State800,
+    u32,
+    add_bytes800,
+    overwrite_bytes800,
+    overwrite_with_zeroes800,
+    extract_bytes800
+);
+
+impl sealed::Sealed for State1600 {}
+
+impl Width for State1600 {
+    #[inline]
+    fn get_p<const ROUNDS: usize>(keccak: &Keccak) -> fn(&mut Self) {
+        <u64 as LaneSize>::get_p::<ROUNDS>(keccak)
+    }
+
+    get_snp!(
+        State1600,
+        add_bytes1600,
+        overwrite_bytes1600,
+        overwrite_with_zeroes1600,
+        extract_bytes1600
+    );
+
+    #[inline]
+    fn absorb_blocks<const ROUNDS: usize>(
+        keccak: &Keccak,
+        p: fn(&mut Self),
+        state: &mut Self,
+        rate: usize,
+        data: &[u8],
+    ) -> usize {
+        struct Closure<'a, const ROUNDS: usize> {
+            state: &'a mut State1600,
+            lane_count: usize,
+            data: &'a [u8],
+            res: &'a mut usize,
+        }
+
+        impl<const ROUNDS: usize> BackendClosure for Closure<'_, ROUNDS> {
+            #[inline(always)]
+            fn call_once<B: Backend>(self) {
+                *self.res =
+                    B::fast_loop_absorb_p1600::<ROUNDS>(self.state, self.lane_count, self.data);
+            }
+        }
+
+        // The fast loop works only with whole lanes
+        if rate % 8 != 0 {
+            let add_bytes = Self::get_snp(keccak).add_bytes;
+            return absorb_blocks_generic(add_bytes, p, state, rate, data);
+        }
+
+        let mut res = 0;
+        keccak.with_backend(Closure::<ROUNDS> {
+            state,
+            lane_count: rate / 8,
+            data,
+            res: &mut res,
+        });
+        res
+    }
+}
+
+/// Keccak sponge over the `W` state with rate of `RATE` bytes and the Keccak-p
+/// permutation with `ROUNDS` rounds.
+///
+/// Padding is parametrized by a "delimited suffix" in the style of XKCP: a byte containing
+/// domain separation bits (starting from the least significant bit) followed by a single
+/// `1` bit. For example, `0x01` is used by the original Keccak, `0x06` by SHA-3,
+/// and `0x1F` by SHAKE.
+///
+/// # Panics
+/// Construction fails at compile time if `RATE` is zero or if it is not smaller
+/// than the state size.
+#[derive(Clone)]
+pub struct Sponge<W: Width, const RATE: usize, const ROUNDS: usize> {
+    keccak: Keccak,
+    p: fn(&mut W),
+    snp: Snp<W>,
+    state: W,
+    pos: usize,
+    squeezing: bool,
+}
+
+impl<W: Width, const RATE: usize, const ROUNDS: usize> Sponge<W, RATE, ROUNDS> {
+    /// Create new sponge with zero initial state.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::new_with_keccak(Keccak::new())
+    }
+
+    /// Create new sponge with zero initial state using the provided [`Keccak`] instance.
+    #[must_use]
+    pub fn new_with_keccak(keccak: Keccak) -> Self {
+        const {
+            assert!(
+                RATE > 0 && RATE < W::BYTE_LEN,
+                "rate must be non-zero and smaller than the state size",
+            );
+        }
+        Self {
+            keccak,
+            p: W::get_p::<ROUNDS>(&keccak),
+            snp: W::get_snp(&keccak),
+            state: W::default(),
+            pos: 0,
+            squeezing: false,
+        }
+    }
+
+    /// Absorb `data` into the sponge.
+    ///
+    /// # Panics
+    /// If the sponge is already in the squeezing phase.
+    pub fn absorb(&mut self, mut data: &[u8]) {
+        assert!(!self.squeezing, "absorb called in the squeezing phase");
+
+        while !data.is_empty() {
+            if self.pos == 0 && data.len() >= RATE {
+                let n =
+                    W::absorb_blocks::<ROUNDS>(&self.keccak, self.p, &mut self.state, RATE, data);
+                data = &data[n..];
+                continue;
+            }
+
+            let n = (RATE - self.pos).min(data.len());
+            (self.snp.add_bytes)(&mut self.state, &data[..n], self.pos);
+            self.pos += n;
+            data = &data[n..];
+
+            if self.pos == RATE {
+                (self.p)(&mut self.state);
+                self.pos = 0;
+            }
+        }
+    }
+
+    /// Absorb the delimited `suffix`, apply `pad10*1` padding, and switch
+    /// to the squeezing phase.
+    ///
+    /// # Panics
+    /// If the sponge is already in the squeezing phase or if `suffix` is equal to zero.
+    pub fn absorb_last(&mut self, suffix: u8) {
+        assert!(!self.squeezing, "absorb_last called in the squeezing phase");
+        assert_ne!(suffix, 0, "delimited suffix must not be zero");
+
+        (self.snp.add_bytes)(&mut self.state, &[suffix], self.pos);
+        // The last padding bit does not fit into the current block
+        if suffix & 0x80 != 0 && self.pos == RATE - 1 {
+            (self.p)(&mut self.state);
+        }
+        (self.snp.add_bytes)(&mut self.state, &[0x80], RATE - 1);
+        (self.p)(&mut self.state);
+
+        self.pos = 0;
+        self.squeezing = true;
+    }
+
+    /// Squeeze output bytes from the sponge.
+    ///
+    /// If the sponge is in the absorbing phase, the padding is applied first
+    /// with the suffix `0x01` (i.e. without domain separation bits).
+    pub fn squeeze(&mut self, mut out: &mut [u8]) {
+        if !self.squeezing {
+            self.absorb_last(0x01);
+        }
+
+        while !out.is_empty() {
+            if self.pos == RATE {
+                (self.p)(&mut self.state);
+                self.pos = 0;
+            }
+
+            let n = (RATE - self.pos).min(out.len());
+            let (head, tail) = out.split_at_mut(n);
+            (self.snp.extract_bytes)(&self.state, head, self.pos);
+            self.pos += n;
+            out = tail;
+        }
+    }
+
+    /// Check if the sponge is in the squeezing phase.
+    #[must_use]
+    pub fn is_squeezing(&self) -> bool {
+        self.squeezing
+    }
+}
+
+impl<W: Width, const RATE: usize, const ROUNDS: usize> Default for Sponge<W, RATE, ROUNDS> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<W: Width, const RATE: usize, const ROUNDS: usize> fmt::Debug for Sponge<W, RATE, ROUNDS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sponge")
+            .field("rate", &RATE)
+            .field("rounds", &ROUNDS)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,305 @@
+//! Step mappings of the Keccak-p round function and intermediate value tracing.
+//!
+//! The state is an array of 25 lanes where the lane with coordinates `(x, y)` has
+//! index `x + 5 * y`. A round with index `ir` consists of [`theta`], [`rho`], [`pi`],
+//! [`chi`], and [`iota`] applied in this order. Keccak-p with `ROUNDS` rounds uses the
+//! last `ROUNDS` round indices of the corresponding Keccak-f permutation, e.g. round
+//! indices `12..24` for Keccak-p\[1600, 12\].
+//!
+//! [`keccak_p_traced`] applies the permutation and passes the state after every step
+//! into a hook. With the `alloc` feature enabled, [`Trace`] records all intermediate states
+//! and exports them in the text layout of the XKCP `IntermediateValues` files or in
+//! the hex format accepted by Verilog `$readmemh`.
+use crate::consts::{PI, PLEN, RHO, round_constant};
+use crate::generic::{LaneSize, keccak_f_round_count};
+
+/// Rotation offsets of rho indexed by the lane index.
+pub(crate) const RHO_OFFSETS: [u32; PLEN] = {
+    let mut offsets = [0; PLEN];
+    let mut pos = 1;
+    let mut i = 0;
+    while i < 24 {
+        offsets[pos] = RHO[i];
+        pos = PI[i];
+        i += 1;
+    }
+    offsets
+};
+
+/// Apply the theta step: add parities of two neighboring columns to every bit.
+pub fn theta<L: LaneSize>(state: &mut [L; PLEN]) {
+    let mut c = [L::default(); 5];
+    for x in 0..5 {
+        c[x] = state[x] ^ state[x + 5] ^ state[x + 10] ^ state[x + 15] ^ state[x + 20];
+    }
+    for x in 0..5 {
+        let d = c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1 % L::BITS);
+        for y in 0..5 {
+            state[5 * y + x] ^= d;
+        }
+    }
+}
+
+/// Apply the rho step: rotate every lane by its offset.
+pub fn rho<L: LaneSize>(state: &mut [L; PLEN]) {
+    for (lane, &offset) in state.iter_mut().zip(RHO_OFFSETS.iter()) {
+        *lane = lane.rotate_left(offset % L::BITS);
+    }
+}
+
+/// Apply the pi step: move the lane `(x, y)` to the position `(y, 2x + 3y)`.
+pub fn pi<L: LaneSize>(state: &mut [L; PLEN]) {
+    let src = *state;
+    let mut pos = 1;
+    for &dst in &PI {
+        state[dst] = src[pos];
+        pos = dst;
+    }
+}
+
+/// Apply the chi step: the non-linear mapping applied to every row.
+pub fn chi<L: LaneSize>(state: &mut [L; PLEN]) {
+    for row in state.chunks_exact_mut(5) {
+        let a = [row[0], row[1], row[2], row[3], row[4]];
+        for x in 0..5 {
+            row[x] = a[x] ^ (!a[(x + 1) % 5] & a[(x + 2) % 5]);
+        }
+    }
+}
+
+/// Apply the iota step: add the round constant of the round with index `ir`
+/// to the lane `(0, 0)`.
+pub fn iota<L: LaneSize>(state: &mut [L; PLEN], ir: i64) {
+    state[0] ^= L::truncate_rc(round_constant(ir));
+}
+
+/// Apply the Keccak-p round with index `ir`.
+pub fn round<L: LaneSize>(state: &mut [L; PLEN], ir: i64) {
+    theta(state);
+    rho(state);
+    pi(state);
+    chi(state);
+    iota(state, ir);
+}
+
+/// Step of the Keccak-p round function.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Step {
+    /// The theta step.
+    Theta,
+    /// The rho step.
+    Rho,
+    /// The pi step.
+    Pi,
+    /// The chi step.
+    Chi,
+    /// The iota step.
+    Iota,
+}
+
+impl Step {
+    /// All steps in the order of application.
+    pub const ALL: [Step; 5] = [Step::Theta, Step::Rho, Step::Pi, Step::Chi, Step::Iota];
+
+    /// Name of the step.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Step::Theta => "theta",
+            Step::Rho => "rho",
+            Step::Pi => "pi",
+            Step::Chi => "chi",
+            Step::Iota => "iota",
+        }
+    }
+
+    /// Apply the step as a part of the round with index `ir`.
+    pub fn apply<L: LaneSize>(self, state: &mut [L; PLEN], ir: i64) {
+        match self {
+            Step::Theta => theta(state),
+            Step::Rho => rho(state),
+            Step::Pi => pi(state),
+            Step::Chi => chi(state),
+            Step::Iota => iota(state, ir),
+        }
+    }
+}
+
+/// Apply Keccak-p with `ROUNDS` rounds to `state` and pass the round index, the step,
+/// and the state after every step into `hook`.
+///
+/// The result is equal to the result of the corresponding `p` function of
+/// [`Keccak`][crate::Keccak].
+#[allow(clippy::cast_possible_wrap)]
+pub fn keccak_p_traced<L: LaneSize, const ROUNDS: usize>(
+    state: &mut [L; PLEN],
+    mut hook: impl FnMut(i64, Step, &[L; PLEN]),
+) {
+    let first = keccak_f_round_count::<L>() as i64 - ROUNDS as i64;
+    for ir in first..first + ROUNDS as i64 {
+        for step in Step::ALL {
+            step.apply(state, ir);
+            hook(ir, step, state);
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+pub use trace::Trace;
+
+#[cfg(feature = "alloc")]
+mod trace {
+    use super::{LaneSize, PLEN, Step, keccak_p_traced};
+    use alloc::vec::Vec;
+    use core::fmt;
+
+    /// Record of all intermediate states of a Keccak-p permutation.
+    #[derive(Clone, Debug)]
+    pub struct Trace<L> {
+        input: [L; PLEN],
+        rounds: usize,
+        steps: Vec<(i64, Step, [L; PLEN])>,
+    }
+
+    impl<L: LaneSize + Into<u64>> Trace<L> {
+        /// Apply Keccak-p with `ROUNDS` rounds to `state` and record all intermediate states.
+        pub fn record<const ROUNDS: usize>(state: &mut [L; PLEN]) -> Self {
+            let input = *state;
+            let mut steps = Vec::with_capacity(5 * ROUNDS);
+            keccak_p_traced::<L, ROUNDS>(state, |ir, step, s| steps.push((ir, step, *s)));
+            Self {
+                input,
+                rounds: ROUNDS,
+                steps,
+            }
+        }
+
+        /// Input state of the permutation.
+        pub fn input(&self) -> &[L; PLEN] {
+            &self.input
+        }
+
+        /// Output state of the permutation.
+        pub fn output(&self) -> &[L; PLEN] {
+            self.steps.last().map_or(&self.input, |(_, _, state)| state)
+        }
+
+        /// Iterate over the round indices, steps, and states after every step.
+        pub fn steps(&self) -> impl Iterator<Item = (i64, Step, &[L; PLEN])> {
+            self.steps
+                .iter()
+                .map(|(ir, step, state)| (*ir, *step, state))
+        }
+
+        /// Write the trace in the text layout of the XKCP `IntermediateValues` files.
+        ///
+        /// States are written as bytes and as lanes before and after the permutation,
+        /// and as lanes after every step of every round.
+        ///
+        /// # Errors
+        /// If writing into `w` fails.
+        pub fn write_xkcp(&self, w: &mut impl fmt::Write) -> fmt::Result {
+            let width = 25 * lane_bits::<L>();
+            writeln!(w, "+++ Keccak-p[{width}, nr={}] +++", self.rounds)?;
+            writeln!(w)?;
+            write_bytes(w, "Input of permutation", &self.input)?;
+            write_lanes(w, &self.input)?;
+            for (ir, step, state) in self.steps() {
+                if step == Step::Theta {
+                    writeln!(w)?;
+                    writeln!(w, "--- Round {ir} ---")?;
+                    writeln!(w)?;
+                }
+                writeln!(w, "After {}:", step.name())?;
+                write_lane_rows(w, state)?;
+            }
+            writeln!(w)?;
+            write_bytes(w, "State after permutation", self.output())?;
+            write_lanes(w, self.output())
+        }
+
+        /// Write the trace in the hex format accepted by Verilog `$readmemh`.
+        ///
+        /// Every state is written on a separate line as a single word of `25 * w` bits,
+        /// where bit `z` of the lane with index `i` is bit `w * i + z` of the word
+        /// (i.e. the lane with index 24 is written first). The input state is followed
+        /// by the states after every step. Every line has a comment with the round index
+        /// and the step name.
+        ///
+        /// # Errors
+        /// If writing into `w` fails.
+        pub fn write_readmemh(&self, w: &mut impl fmt::Write) -> fmt::Result {
+            write_word(w, &self.input)?;
+            writeln!(w, " // input")?;
+            for (ir, step, state) in self.steps() {
+                write_word(w, state)?;
+                writeln!(w, " // round {ir} {}", step.name())?;
+            }
+            Ok(())
+        }
+    }
+
+    /// Number of bits in a lane.
+    fn lane_bits<L: LaneSize>() -> usize {
+        L::BITS as usize
+    }
+
+    /// Write `state` as bytes with 16 bytes per line.
+    fn write_bytes<L: LaneSize + Into<u64>>(
+        w: &mut impl fmt::Write,
+        title: &str,
+        state: &[L; PLEN],
+    ) -> fmt::Result {
+        writeln!(w, "{title}:")?;
+        let lane_len = (L::BITS / 8) as usize;
+        let bytes = state
+            .iter()
+            .flat_map(|&lane| lane.into().to_le_bytes().into_iter().take(lane_len));
+        for (i, byte) in bytes.enumerate() {
+            write!(w, "{byte:02X} ")?;
+            if i % 16 == 15 {
+                writeln!(w)?;
+            }
+        }
+        if (PLEN * lane_len) % 16 != 0 {
+            writeln!(w)?;
+        }
+        Ok(())
+    }
+
+    /// Write `state` as lanes with a title describing the lane size.
+    fn write_lanes<L: LaneSize + Into<u64>>(
+        w: &mut impl fmt::Write,
+        state: &[L; PLEN],
+    ) -> fmt::Result {
+        writeln!(w, "Same, with lanes as {}-bit words:", lane_bits::<L>())?;
+        write_lane_rows(w, state)
+    }
+
+    /// Write `state` as lanes with 5 lanes per line.
+    fn write_lane_rows<L: LaneSize + Into<u64>>(
+        w: &mut impl fmt::Write,
+        state: &[L; PLEN],
+    ) -> fmt::Result {
+        let digits = lane_bits::<L>() / 4;
+        for row in state.chunks_exact(5) {
+            for &lane in row {
+                write!(w, "{:0digits$X} ", lane.into())?;
+            }
+            writeln!(w)?;
+        }
+        Ok(())
+    }
+
+    /// Write `state` as a single hex word with the last lane first.
+    fn write_word<L: LaneSize + Into<u64>>(
+        w: &mut impl fmt::Write,
+        state: &[L; PLEN],
+    ) -> fmt::Result {
+        let digits = lane_bits::<L>() / 4;
+        for &lane in state.iter().rev() {
+            write!(w, "{:0digits$X}", lane.into())?;
+        }
+        Ok(())
+    }
+}
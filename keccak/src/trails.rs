@@ -0,0 +1,460 @@
+//! Propagation of differences and linear masks through Keccak-f.
+//!
+//! A round of Keccak-f consists of the linear part `λ = π ∘ ρ ∘ θ` followed by the
+//! non-linear step χ. Iota does not affect differences or masks.
+//!
+//! The output differences of χ compatible with an input difference `a` form an affine
+//! space, every element of which is reached with probability `2^-w(a)`, where `w(a)` is
+//! the differential weight returned by [`chi_weight`]. Similarly, the input masks
+//! of χ compatible with an output mask `b` form an affine space, every element of which
+//! has the squared correlation `2^-w(b)` with `b`, where `w(b)` is the linear weight
+//! returned by [`chi_linear_weight`]. Weights are sums of the weights of the rows.
+//!
+//! Differential trails are described by the differences at the χ inputs, and linear
+//! trails by the masks at the χ outputs, since these determine the round weights.
+//! The trail weight is the sum of the round weights.
+//!
+//! Bit `z` of the lane with index `i` of a state is the bit with coordinates
+//! `(i % 5, i / 5, z)`.
+use crate::consts::PLEN;
+use crate::generic::LaneSize;
+use crate::inverse::theta_inverse_coeffs;
+use crate::steps::{self, RHO_OFFSETS};
+use core::fmt;
+
+/// Compute output of χ for the 5-bit row `x`.
+const fn chi_row(x: u32) -> u32 {
+    let mut res = 0;
+    let mut i = 0;
+    while i < 5 {
+        let bit = (x >> i) ^ (!(x >> ((i + 1) % 5)) & (x >> ((i + 2) % 5)));
+        res |= (bit & 1) << i;
+        i += 1;
+    }
+    res
+}
+
+/// Affine space of 5-bit rows.
+#[derive(Clone, Copy)]
+struct RowSpace {
+    /// Bit `v` is set if the row `v` is an element of the space.
+    set: u32,
+    #[cfg_attr(not(feature = "alloc"), allow(dead_code))]
+    offset: u8,
+    #[cfg_attr(not(feature = "alloc"), allow(dead_code))]
+    basis: [u8; 5],
+    dim: u8,
+}
+
+impl RowSpace {
+    /// Build the space from the set of its elements.
+    #[allow(clippy::cast_possible_truncation)]
+    const fn new(set: u32) -> Self {
+        let offset = set.trailing_zeros() as u8;
+        let mut by_bit = [0u8; 5];
+        let mut v: u8 = 0;
+        while v < 32 {
+            if (set >> v) & 1 == 1 {
+                let mut x = v ^ offset;
+                let mut bit = 5;
+                while bit > 0 {
+                    bit -= 1;
+                    if (x >> bit) & 1 == 1 {
+                        if by_bit[bit] == 0 {
+                            by_bit[bit] = x;
+                            break;
+                        }
+                        x ^= by_bit[bit];
+                    }
+                }
+            }
+            v += 1;
+        }
+
+        let mut basis = [0; 5];
+        let mut dim = 0;
+        let mut bit = 0;
+        while bit < 5 {
+            if by_bit[bit] != 0 {
+                basis[dim] = by_bit[bit];
+                dim += 1;
+            }
+            bit += 1;
+        }
+        Self {
+            set,
+            offset,
+            basis,
+            dim: dim as u8,
+        }
+    }
+}
+
+/// Spaces of the output differences of χ indexed by the row input difference.
+const DIFF_SPACES: [RowSpace; 32] = {
+    let mut res = [RowSpace::new(1); 32];
+    let mut a = 0;
+    while a < 32 {
+        let mut set = 0;
+        let mut x = 0;
+        while x < 32 {
+            set |= 1 << (chi_row(x) ^ chi_row(x ^ a));
+            x += 1;
+        }
+        res[a as usize] = RowSpace::new(set);
+        a += 1;
+    }
+    res
+};
+
+/// Spaces of the input masks of χ indexed by the row output mask.
+const MASK_SPACES: [RowSpace; 32] = {
+    let mut res = [RowSpace::new(1); 32];
+    let mut b = 0;
+    while b < 32 {
+        let mut set = 0;
+        let mut a: u32 = 0;
+        while a < 32 {
+            let mut corr = 0i32;
+            let mut x = 0;
+            while x < 32 {
+                if ((a & x).count_ones() + (b & chi_row(x)).count_ones()) % 2 == 0 {
+                    corr += 1;
+                } else {
+                    corr -= 1;
+                }
+                x += 1;
+            }
+            if corr != 0 {
+                set |= 1 << a;
+            }
+            a += 1;
+        }
+        res[b as usize] = RowSpace::new(set);
+        b += 1;
+    }
+    res
+};
+
+/// Get the row with coordinates `(y, z)` of `state`.
+fn row<L: LaneSize + Into<u64>>(state: &[L; PLEN], y: usize, z: u32) -> usize {
+    (0..5).fold(0, |row, x| {
+        row | usize::from((state[x + 5 * y].into() >> z) & 1 == 1) << x
+    })
+}
+
+/// Sum of the weights of the row spaces of `state`.
+fn weight<L: LaneSize + Into<u64>>(state: &[L; PLEN], spaces: &[RowSpace; 32]) -> u32 {
+    let mut res = 0;
+    for y in 0..5 {
+        for z in 0..L::BITS {
+            res += u32::from(spaces[row(state, y, z)].dim);
+        }
+    }
+    res
+}
+
+/// Check whether every row of `b` is in the space of the corresponding row of `a`.
+fn is_compatible<L: LaneSize + Into<u64>>(
+    a: &[L; PLEN],
+    b: &[L; PLEN],
+    spaces: &[RowSpace; 32],
+) -> bool {
+    (0..5).all(|y| (0..L::BITS).all(|z| (spaces[row(a, y, z)].set >> row(b, y, z)) & 1 == 1))
+}
+
+/// Differential weight of χ for the input difference `diff`.
+///
+/// Every compatible output difference is reached with probability `2^-w`.
+#[must_use]
+pub fn chi_weight<L: LaneSize + Into<u64>>(diff: &[L; PLEN]) -> u32 {
+    weight(diff, &DIFF_SPACES)
+}
+
+/// Check whether χ can propagate the input difference `input` to the output
+/// difference `output`.
+#[must_use]
+pub fn is_chi_compatible<L: LaneSize + Into<u64>>(input: &[L; PLEN], output: &[L; PLEN]) -> bool {
+    is_compatible(input, output, &DIFF_SPACES)
+}
+
+/// Linear weight of χ for the output mask `mask`.
+///
+/// Every compatible input mask has the squared correlation `2^-w` with `mask`.
+#[must_use]
+pub fn chi_linear_weight<L: LaneSize + Into<u64>>(mask: &[L; PLEN]) -> u32 {
+    weight(mask, &MASK_SPACES)
+}
+
+/// Check whether the input mask `input` of χ has a non-zero correlation
+/// with the output mask `output`.
+#[must_use]
+pub fn is_chi_linear_compatible<L: LaneSize + Into<u64>>(
+    input: &[L; PLEN],
+    output: &[L; PLEN],
+) -> bool {
+    is_compatible(output, input, &MASK_SPACES)
+}
+
+/// Compute parities of the columns of `state` as lanes indexed by `x`.
+#[must_use]
+pub fn column_parity<L: LaneSize>(state: &[L; PLEN]) -> [L; 5] {
+    core::array::from_fn(|x| {
+        state[x] ^ state[x + 5] ^ state[x + 10] ^ state[x + 15] ^ state[x + 20]
+    })
+}
+
+/// Check whether `state` is in the column parity kernel, i.e. whether all columns
+/// have even parity. θ acts as the identity on the states in the kernel.
+#[must_use]
+pub fn is_in_kernel<L: LaneSize + Into<u64>>(state: &[L; PLEN]) -> bool {
+    column_parity(state)
+        .into_iter()
+        .all(|lane| lane.into() == 0)
+}
+
+/// Add `M C` to every row of `state`, where `C` is the column parity and `M` is
+/// the polynomial `coeffs` over `t` and `z` in the notation of the [`inverse`][crate::inverse]
+/// module. With `transposed` the transposed map is applied.
+fn add_parity_product<L: LaneSize>(state: &mut [L; PLEN], coeffs: &[u64; 5], transposed: bool) {
+    let c = column_parity(state);
+    let mut e = [L::default(); 5];
+    for (k, &coeff) in coeffs.iter().enumerate() {
+        for r in (0..L::BITS).filter(|&r| (coeff >> r) & 1 == 1) {
+            for x in 0..5 {
+                e[x] ^= if transposed {
+                    c[(x + k) % 5].rotate_left((L::BITS - r) % L::BITS)
+                } else {
+                    c[(x + 5 - k) % 5].rotate_left(r)
+                };
+            }
+        }
+    }
+    for (i, lane) in state.iter_mut().enumerate() {
+        *lane ^= e[i % 5];
+    }
+}
+
+/// Polynomial `t + t^4 z` of θ.
+fn theta_coeffs<L: LaneSize>() -> [u64; 5] {
+    [0, 1, 0, 0, 1 << (1 % L::BITS)]
+}
+
+/// Polynomial of the inverse of θ reduced by 1.
+fn theta_inverse_coeffs_minus_one<L: LaneSize>() -> [u64; 5] {
+    let mut res = const { theta_inverse_coeffs(L::BITS) };
+    res[0] ^= 1;
+    res
+}
+
+/// Apply the inverse of ρ.
+fn rho_inverse<L: LaneSize>(state: &mut [L; PLEN]) {
+    for (lane, &offset) in state.iter_mut().zip(RHO_OFFSETS.iter()) {
+        *lane = lane.rotate_left((L::BITS - offset % L::BITS) % L::BITS);
+    }
+}
+
+/// Apply the inverse of π.
+fn pi_inverse<L: LaneSize>(state: &mut [L; PLEN]) {
+    let src = *state;
+    for x in 0..5 {
+        for y in 0..5 {
+            state[(x + 3 * y) % 5 + 5 * x] = src[x + 5 * y];
+        }
+    }
+}
+
+/// Apply the linear part `λ = π ∘ ρ ∘ θ` of the round function.
+pub fn lambda<L: LaneSize>(state: &mut [L; PLEN]) {
+    steps::theta(state);
+    steps::rho(state);
+    steps::pi(state);
+}
+
+/// Apply the inverse of `λ`.
+pub fn lambda_inverse<L: LaneSize>(state: &mut [L; PLEN]) {
+    pi_inverse(state);
+    rho_inverse(state);
+    add_parity_product(state, &theta_inverse_coeffs_minus_one::<L>(), false);
+}
+
+/// Apply the transpose of `λ`, which maps masks at the output of `λ` to masks at its input.
+pub fn lambda_transpose<L: LaneSize>(state: &mut [L; PLEN]) {
+    pi_inverse(state);
+    rho_inverse(state);
+    add_parity_product(state, &theta_coeffs::<L>(), true);
+}
+
+/// Apply the inverse of the transpose of `λ`, which maps masks at the input of `λ`
+/// to masks at its output.
+pub fn lambda_inverse_transpose<L: LaneSize>(state: &mut [L; PLEN]) {
+    add_parity_product(state, &theta_inverse_coeffs_minus_one::<L>(), true);
+    steps::rho(state);
+    steps::pi(state);
+}
+
+/// Error returned for trails with incompatible consecutive rounds.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct IncompatibleTrail {
+    round: usize,
+}
+
+impl IncompatibleTrail {
+    /// Index of the round whose χ can not propagate to the state of the next round.
+    #[must_use]
+    pub fn round(&self) -> usize {
+        self.round
+    }
+}
+
+impl fmt::Display for IncompatibleTrail {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "trail round {} is incompatible with round {}",
+            self.round,
+            self.round + 1,
+        )
+    }
+}
+
+impl core::error::Error for IncompatibleTrail {}
+
+/// Compute the weight of the differential trail with differences `states` at the χ inputs
+/// of consecutive rounds.
+///
+/// The difference `λ^-1(states[i + 1])` must be a compatible output difference of χ
+/// for `states[i]`.
+///
+/// # Errors
+/// If consecutive rounds of the trail are incompatible.
+pub fn differential_trail_weight<L: LaneSize + Into<u64>>(
+    states: &[[L; PLEN]],
+) -> Result<u32, IncompatibleTrail> {
+    for (round, pair) in states.windows(2).enumerate() {
+        let mut output = pair[1];
+        lambda_inverse(&mut output);
+        if !is_chi_compatible(&pair[0], &output) {
+            return Err(IncompatibleTrail { round });
+        }
+    }
+    Ok(states.iter().map(chi_weight).sum())
+}
+
+/// Compute the weight of the linear trail with masks `states` at the χ outputs
+/// of consecutive rounds.
+///
+/// The mask `λ^-T(states[i])` must be a compatible input mask of χ for `states[i + 1]`.
+///
+/// # Errors
+/// If consecutive rounds of the trail are incompatible.
+pub fn linear_trail_weight<L: LaneSize + Into<u64>>(
+    states: &[[L; PLEN]],
+) -> Result<u32, IncompatibleTrail> {
+    for (round, pair) in states.windows(2).enumerate() {
+        let mut input = pair[0];
+        lambda_inverse_transpose(&mut input);
+        if !is_chi_linear_compatible(&input, &pair[1]) {
+            return Err(IncompatibleTrail { round });
+        }
+    }
+    Ok(states.iter().map(chi_linear_weight).sum())
+}
+
+#[cfg(feature = "alloc")]
+pub use space::{AffineSpace, chi_input_masks, chi_output_differences};
+
+#[cfg(feature = "alloc")]
+mod space {
+    use super::{DIFF_SPACES, LaneSize, MASK_SPACES, PLEN, RowSpace, row};
+    use alloc::vec::Vec;
+
+    /// Affine space of states.
+    #[derive(Clone, Debug)]
+    pub struct AffineSpace<L> {
+        offset: [L; PLEN],
+        basis: Vec<[L; PLEN]>,
+    }
+
+    impl<L: LaneSize> AffineSpace<L> {
+        fn from_rows(state: &[L; PLEN], spaces: &[RowSpace; 32]) -> Self
+        where
+            L: Into<u64>,
+        {
+            let mut offset = [L::default(); PLEN];
+            let mut basis = Vec::new();
+            for y in 0..5 {
+                for z in 0..L::BITS {
+                    let space = &spaces[row(state, y, z)];
+                    add_row(&mut offset, y, z, space.offset);
+                    for &v in &space.basis[..usize::from(space.dim)] {
+                        let mut b = [L::default(); PLEN];
+                        add_row(&mut b, y, z, v);
+                        basis.push(b);
+                    }
+                }
+            }
+            Self { offset, basis }
+        }
+
+        /// Element of the space.
+        #[must_use]
+        pub fn offset(&self) -> &[L; PLEN] {
+            &self.offset
+        }
+
+        /// Basis of the linear space parallel to this space.
+        #[must_use]
+        pub fn basis(&self) -> &[[L; PLEN]] {
+            &self.basis
+        }
+
+        /// Dimension of the space. The space has `2^dimension` elements.
+        #[must_use]
+        pub fn dimension(&self) -> usize {
+            self.basis.len()
+        }
+
+        /// Iterate over all elements of the space.
+        ///
+        /// # Panics
+        /// If dimension of the space is 64 or bigger.
+        pub fn iter(&self) -> impl Iterator<Item = [L; PLEN]> + '_ {
+            assert!(self.dimension() < 64, "space is too big to iterate");
+            (0..1u64 << self.dimension()).map(|i| {
+                let mut res = self.offset;
+                for (j, b) in self.basis.iter().enumerate() {
+                    if (i >> j) & 1 == 1 {
+                        for (lane, &b) in res.iter_mut().zip(b) {
+                            *lane ^= b;
+                        }
+                    }
+                }
+                res
+            })
+        }
+    }
+
+    /// Add the 5-bit `row` to the row with coordinates `(y, z)` of `state`.
+    fn add_row<L: LaneSize>(state: &mut [L; PLEN], y: usize, z: u32, row: u8) {
+        for x in 0..5 {
+            if (row >> x) & 1 == 1 {
+                state[x + 5 * y] ^= L::truncate_rc(1 << z);
+            }
+        }
+    }
+
+    /// Compute the space of the output differences of χ compatible with
+    /// the input difference `diff`.
+    #[must_use]
+    pub fn chi_output_differences<L: LaneSize + Into<u64>>(diff: &[L; PLEN]) -> AffineSpace<L> {
+        AffineSpace::from_rows(diff, &DIFF_SPACES)
+    }
+
+    /// Compute the space of the input masks of χ with a non-zero correlation
+    /// with the output mask `mask`.
+    #[must_use]
+    pub fn chi_input_masks<L: LaneSize + Into<u64>>(mask: &[L; PLEN]) -> AffineSpace<L> {
+        AffineSpace::from_rows(mask, &MASK_SPACES)
+    }
+}
@@ -21,12 +21,25 @@ pub type Fn1600 = fn(&mut State1600);
 
 #[cfg(feature = "parallel")]
 mod parallel {
-    use super::State1600;
+    use super::{State200, State400, State800, State1600};
     use crate::Backend;
     use hybrid_array::Array;
 
+    /// 200xN-bit state processed in parallel by a [`Backend`] implementation.
+    pub type ParState200<B> = Array<State200, <B as Backend>::ParSize200>;
+    /// 400xN-bit state processed in parallel by a [`Backend`] implementation.
+    pub type ParState400<B> = Array<State400, <B as Backend>::ParSize400>;
+    /// 800xN-bit state processed in parallel by a [`Backend`] implementation.
+    pub type ParState800<B> = Array<State800, <B as Backend>::ParSize800>;
     /// 1600xN-bit state processed in parallel by a [`Backend`] implementation.
     pub type ParState1600<B> = Array<State1600, <B as Backend>::ParSize1600>;
+
+    /// A Keccak function which permutates [`ParState200`].
+    pub type ParFn200<B> = fn(&mut ParState200<B>);
+    /// A Keccak function which permutates [`ParState400`].
+    pub type ParFn400<B> = fn(&mut ParState400<B>);
+    /// A Keccak function which permutates [`ParState800`].
+    pub type ParFn800<B> = fn(&mut ParState800<B>);
     /// A Keccak function which permutates [`ParState1600`].
     pub type ParFn1600<B> = fn(&mut ParState1600<B>);
 }
@@ -0,0 +1,198 @@
+//! Tests of the algebraic representations of Keccak-p.
+#![cfg(feature = "alloc")]
+use keccak::PLEN;
+use keccak::analysis::{self, Anf, CnfBit, InputBit};
+use keccak::generic::{LaneSize, keccak_p};
+
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_mul(0x5851_F42D_4C95_7F2D).wrapping_add(1);
+        self.0 >> 11
+    }
+
+    fn bits(&mut self, n: usize) -> Vec<bool> {
+        (0..n).map(|_| self.next() & 1 == 1).collect()
+    }
+}
+
+/// Pack `bits` into a state with lanes of type `L`.
+fn to_state<L: LaneSize + Into<u64> + TryFrom<u64>>(bits: &[bool]) -> [L; PLEN] {
+    let w = L::BITS as usize;
+    core::array::from_fn(|i| {
+        let lane = (0..w).fold(0u64, |lane, z| lane | u64::from(bits[w * i + z]) << z);
+        L::try_from(lane).ok().expect("lane fits")
+    })
+}
+
+/// Apply Keccak-p to `input` with variables replaced by `vars` and return the output bits.
+fn reference<L: LaneSize + Into<u64> + TryFrom<u64>, const ROUNDS: usize>(
+    input: &[InputBit],
+    vars: &[bool],
+) -> Vec<bool> {
+    let mut vars = vars.iter();
+    let bits: Vec<bool> = input
+        .iter()
+        .map(|bit| match *bit {
+            InputBit::Variable => *vars.next().expect("value of variable"),
+            InputBit::Constant(value) => value,
+        })
+        .collect();
+    let mut state = to_state::<L>(&bits);
+    keccak_p::<L, ROUNDS>(&mut state);
+    let w = L::BITS;
+    state
+        .iter()
+        .flat_map(|&lane| {
+            let lane = lane.into();
+            (0..w).map(move |z| (lane >> z) & 1 == 1)
+        })
+        .collect()
+}
+
+/// Input with every `step`-th bit being a variable and the remaining bits random.
+fn input<L: LaneSize + Into<u64> + TryFrom<u64>>(rng: &mut Rng, step: usize) -> Vec<InputBit> {
+    let w = L::BITS as usize;
+    let state = to_state::<L>(&rng.bits(PLEN * w));
+    let mut input = analysis::constant_input(&state);
+    for bit in input.iter_mut().step_by(step) {
+        *bit = InputBit::Variable;
+    }
+    input
+}
+
+fn check_anf<L: LaneSize + Into<u64> + TryFrom<u64>, const ROUNDS: usize>(
+    step: usize,
+    max_degree: usize,
+) {
+    let mut rng = Rng(ROUNDS as u64);
+    let input = input::<L>(&mut rng, step);
+    let vars = input
+        .iter()
+        .filter(|&&bit| bit == InputBit::Variable)
+        .count();
+    let anf = analysis::anf::<L, ROUNDS>(&input);
+    assert_eq!(anf.len(), input.len());
+    assert!(anf.iter().all(|p| p.degree() <= max_degree));
+    for _ in 0..16 {
+        let assignment = rng.bits(vars);
+        let res: Vec<bool> = anf.iter().map(|p| p.evaluate(&assignment)).collect();
+        assert_eq!(res, reference::<L, ROUNDS>(&input, &assignment));
+    }
+}
+
+#[test]
+fn anf_matches_keccak_p() {
+    check_anf::<u8, 0>(1, 1);
+    check_anf::<u8, 1>(1, 2);
+    check_anf::<u8, 2>(13, 4);
+    check_anf::<u8, 3>(40, 8);
+    check_anf::<u16, 1>(1, 2);
+    check_anf::<u16, 2>(37, 4);
+    check_anf::<u32, 1>(1, 2);
+    check_anf::<u64, 1>(3, 2);
+}
+
+fn check_cnf<L: LaneSize + Into<u64> + TryFrom<u64>, const ROUNDS: usize>(step: usize) {
+    let mut rng = Rng(ROUNDS as u64);
+    let input = input::<L>(&mut rng, step);
+    let cnf = analysis::cnf::<L, ROUNDS>(&input);
+    assert_eq!(cnf.outputs().len(), input.len());
+    for _ in 0..4 {
+        let inputs = rng.bits(cnf.num_inputs());
+        let mut assignment = cnf.assignment(&inputs);
+        assert_eq!(assignment.len(), cnf.num_vars());
+        assert!(cnf.is_satisfied(&assignment));
+        let res: Vec<bool> = cnf.outputs().iter().map(|b| b.value(&assignment)).collect();
+        assert_eq!(res, reference::<L, ROUNDS>(&input, &inputs));
+
+        // Values of the auxiliary variables are determined by the input variables
+        for var in (cnf.num_inputs()..cnf.num_vars()).step_by(97) {
+            assignment[var] = !assignment[var];
+            assert!(!cnf.is_satisfied(&assignment));
+            assignment[var] = !assignment[var];
+        }
+    }
+}
+
+#[test]
+fn cnf_matches_keccak_p() {
+    check_cnf::<u8, 1>(1);
+    check_cnf::<u8, 18>(1);
+    check_cnf::<u8, 22>(5);
+    check_cnf::<u16, 20>(1);
+    check_cnf::<u32, 4>(2);
+    check_cnf::<u64, 2>(1);
+}
+
+#[test]
+fn constant_input() {
+    let mut state = [0x0123_4567_89AB_CDEFu64; PLEN];
+    let input = analysis::constant_input(&state);
+    keccak_p::<u64, 3>(&mut state);
+    let expected = analysis::constant_input(&state);
+
+    let cnf = analysis::cnf::<u64, 3>(&input);
+    assert_eq!(cnf.num_vars(), 0);
+    assert!(cnf.clauses().is_empty());
+    for (bit, expected) in cnf.outputs().iter().zip(&expected) {
+        assert_eq!(*expected, InputBit::Constant(bit.value(&[])));
+    }
+
+    for (p, expected) in analysis::anf::<u64, 3>(&input).iter().zip(&expected) {
+        let value = p.as_constant().expect("constant output");
+        assert_eq!(InputBit::Constant(value), *expected);
+    }
+}
+
+#[test]
+fn anf_ops() {
+    let x0 = Anf::variable(0);
+    let x1 = Anf::variable(1);
+    let one = Anf::constant(true);
+    assert_eq!(Anf::default().to_string(), "0");
+    assert_eq!(one.to_string(), "1");
+    assert_eq!(Anf::constant(false).as_constant(), Some(false));
+    assert_eq!(one.as_constant(), Some(true));
+    assert_eq!(x0.as_constant(), None);
+
+    let p = &(&x0 & &x1) ^ &!&x1;
+    assert_eq!(p.to_string(), "1 + x0*x1 + x1");
+    assert_eq!(p.degree(), 2);
+    assert_eq!(p.monomials().collect::<Vec<_>>(), [&[][..], &[0, 1], &[1]]);
+    assert!(p.evaluate(&[false, false]));
+    assert!(!p.evaluate(&[false, true]));
+    assert!(p.evaluate(&[true, true]));
+
+    assert_eq!(&p ^ &p, Anf::default());
+    assert_eq!(&x0 & &x0, x0);
+    assert_eq!(&(&x0 ^ &one) & &x0, Anf::default());
+}
+
+#[test]
+fn dimacs() {
+    let mut input = vec![InputBit::Constant(false); 200];
+    input[0] = InputBit::Variable;
+    input[9] = InputBit::Variable;
+    let cnf = analysis::cnf::<u8, 1>(&input);
+    assert!(matches!(cnf.outputs()[0], CnfBit::Literal(_)));
+
+    let mut out = String::new();
+    cnf.write_dimacs(&mut out).expect("writing into string");
+    let mut lines = out.lines();
+    assert_eq!(lines.next(), Some("c Keccak-p[200, nr=1]"));
+    assert_eq!(lines.next(), Some("c input variables: 1..=2"));
+    let header = format!("p cnf {} {}", cnf.num_vars(), cnf.clauses().len());
+    let mut lines = lines.skip_while(|line| line.starts_with('c'));
+    assert_eq!(lines.next(), Some(header.as_str()));
+    for (line, clause) in lines.by_ref().zip(cnf.clauses()) {
+        let lits: Vec<i32> = line
+            .split(' ')
+            .map(|lit| lit.parse().expect("literal"))
+            .collect();
+        assert_eq!(lits[..lits.len() - 1], clause[..]);
+        assert_eq!(lits.last(), Some(&0));
+    }
+    assert_eq!(lines.next(), None);
+}
@@ -0,0 +1,219 @@
+//! Tests for the byte-oriented state interface.
+use core::array;
+use keccak::{Backend, BackendClosure, State200, State400, State800, State1600, StateBytes};
+
+/// Test all state functions against a reference model operating on a byte array.
+macro_rules! impl_test {
+    ($name:ident, $state:ty, $lane:ty) => {
+        #[test]
+        #[allow(clippy::cast_possible_truncation, trivial_numeric_casts)]
+        fn $name() {
+            const LEN: usize = <$state as StateBytes>::BYTE_LEN;
+
+            fn to_bytes(state: &$state) -> [u8; LEN] {
+                let mut res = [0u8; LEN];
+                for (chunk, lane) in res.chunks_exact_mut(size_of::<$lane>()).zip(state) {
+                    chunk.copy_from_slice(&lane.to_le_bytes());
+                }
+                res
+            }
+
+            let state: $state =
+                array::from_fn(|i| (i as $lane).wrapping_mul(0x9E37_79B9_7F4A_7C15u64 as $lane));
+            let data: [u8; LEN] = array::from_fn(|i| (i as u8).wrapping_mul(0x3B) ^ 0xA5);
+
+            // Exhaustive test is too slow under Miri
+            let step = if cfg!(miri) { 13 } else { 1 };
+
+            for offset in (0..=LEN).step_by(step) {
+                for len in (0..=LEN - offset).step_by(step) {
+                    let data = &data[..len];
+                    let expected = to_bytes(&state);
+
+                    let mut s = state;
+                    s.add_bytes(data, offset);
+                    let mut e = expected;
+                    e[offset..][..len]
+                        .iter_mut()
+                        .zip(data)
+                        .for_each(|(a, b)| *a ^= b);
+                    assert_eq!(to_bytes(&s), e);
+
+                    let mut s = state;
+                    s.overwrite_bytes(data, offset);
+                    let mut e = expected;
+                    e[offset..][..len].copy_from_slice(data);
+                    assert_eq!(to_bytes(&s), e);
+
+                    let mut out = [0u8; LEN];
+                    state.extract_bytes(&mut out[..len], offset);
+                    assert_eq!(out[..len], expected[offset..][..len]);
+
+                    let mut out = [0u8; LEN];
+                    state.extract_and_add_bytes(data, &mut out[..len], offset);
+                    let e: [u8; LEN] = array::from_fn(|i| {
+                        if i < len {
+                            data[i] ^ expected[offset + i]
+                        } else {
+                            0
+                        }
+                    });
+                    assert_eq!(out, e);
+                }
+
+                let mut s = state;
+                s.overwrite_with_zeroes(offset);
+                let mut e = to_bytes(&state);
+                e[..offset].fill(0);
+                assert_eq!(to_bytes(&s), e);
+            }
+        }
+    };
+}
+
+impl_test!(state200_bytes, State200, u8);
+impl_test!(state400_bytes, State400, u16);
+impl_test!(state800_bytes, State800, u32);
+impl_test!(state1600_bytes, State1600, u64);
+
+#[test]
+#[should_panic]
+fn add_bytes_out_of_bounds() {
+    let mut state = State1600::default();
+    state.add_bytes(&[1, 2, 3], 198);
+}
+
+macro_rules! impl_backend_test {
+    (
+        $name:ident, $state:ident, $len:literal, $add:ident, $overwrite:ident, $zeroes:ident,
+        $extract:ident, $extract_and_add:ident
+    ) => {
+        #[test]
+        #[allow(clippy::cast_possible_truncation)]
+        fn $name() {
+            struct Closure;
+
+            impl BackendClosure for Closure {
+                fn call_once<B: Backend>(self) {
+                    const LEN: usize = $len;
+                    let data: [u8; LEN] = array::from_fn(|i| (i as u8).wrapping_mul(29));
+                    let mut state = $state::default();
+                    B::$overwrite(&mut state, &data[..LEN / 2], 3);
+                    B::$add(&mut state, &data[..LEN / 4], 5);
+                    B::$zeroes(&mut state, 2);
+
+                    let mut expected = $state::default();
+                    expected.overwrite_bytes(&data[..LEN / 2], 3);
+                    expected.add_bytes(&data[..LEN / 4], 5);
+                    expected.overwrite_with_zeroes(2);
+                    assert_eq!(state, expected);
+
+                    let mut out = [0u8; LEN];
+                    let out = &mut out[..LEN * 3 / 4];
+                    B::$extract(&state, out, LEN / 5);
+                    let mut expected_out = [0u8; LEN];
+                    let expected_out = &mut expected_out[..LEN * 3 / 4];
+                    expected.extract_bytes(expected_out, LEN / 5);
+                    assert_eq!(out, expected_out);
+
+                    let input = &data[..LEN * 3 / 4];
+                    B::$extract_and_add(&state, input, out, LEN / 4);
+                    expected.extract_and_add_bytes(input, expected_out, LEN / 4);
+                    assert_eq!(out, expected_out);
+                }
+            }
+
+            keccak::Keccak::new().with_backend(Closure);
+        }
+    };
+}
+
+impl_backend_test!(
+    backend_bytes200,
+    State200,
+    25,
+    add_bytes200,
+    overwrite_bytes200,
+    overwrite_with_zeroes200,
+    extract_bytes200,
+    extract_and_add_bytes200
+);
+impl_backend_test!(
+    backend_bytes400,
+    State400,
+    50,
+    add_bytes400,
+    overwrite_bytes400,
+    overwrite_with_zeroes400,
+    extract_bytes400,
+    extract_and_add_bytes400
+);
+impl_backend_test!(
+    backend_bytes800,
+    State800,
+    100,
+    add_bytes800,
+    overwrite_bytes800,
+    overwrite_with_zeroes800,
+    extract_bytes800,
+    extract_and_add_bytes800
+);
+impl_backend_test!(
+    backend_bytes1600,
+    State1600,
+    200,
+    add_bytes1600,
+    overwrite_bytes1600,
+    overwrite_with_zeroes1600,
+    extract_bytes1600,
+    extract_and_add_bytes1600
+);
+
+#[test]
+#[allow(clippy::cast_possible_truncation)]
+fn backend_fast_loop_absorb() {
+    struct Closure;
+
+    impl BackendClosure for Closure {
+        fn call_once<B: Backend>(self) {
+            let data: [u8; 700] = array::from_fn(|i| (i as u8).wrapping_mul(0x3B) ^ 0xA5);
+            let f1600 = B::get_f1600();
+            let p1600 = B::get_p1600::<12>();
+
+            for lane_count in 1..=25 {
+                let block_len = 8 * lane_count;
+                for len in [
+                    0,
+                    1,
+                    block_len - 1,
+                    block_len,
+                    3 * block_len + 5,
+                    data.len(),
+                ] {
+                    let data = &data[..len];
+                    let full_len = len - len % block_len;
+
+                    let mut state: State1600 = array::from_fn(|i| i as u64);
+                    let mut expected = state;
+                    let n = B::fast_loop_absorb_f1600(&mut state, lane_count, data);
+                    for block in data.chunks_exact(block_len) {
+                        expected.add_bytes(block, 0);
+                        f1600(&mut expected);
+                    }
+                    assert_eq!(n, full_len);
+                    assert_eq!(state, expected);
+
+                    let n = B::fast_loop_absorb_p1600::<12>(&mut state, lane_count, data);
+                    for block in data.chunks_exact(block_len) {
+                        expected.add_bytes(block, 0);
+                        p1600(&mut expected);
+                    }
+                    assert_eq!(n, full_len);
+                    assert_eq!(state, expected);
+                }
+            }
+        }
+    }
+
+    keccak::Keccak::new().with_backend(Closure);
+}
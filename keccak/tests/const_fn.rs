@@ -0,0 +1,164 @@
+//! Tests of the const Keccak functions.
+use hex_literal::hex;
+use keccak::{
+    Keccak, State200, State400, State800, State1600,
+    const_fn::{self, ConstSponge},
+    fips202,
+    sp800_185::{self, left_encode, right_encode},
+};
+
+fn test_state<T>(f: impl Fn(u64) -> T) -> [T; 25] {
+    let mut x = 0x0123_4567_89AB_CDEFu64;
+    core::array::from_fn(|_| {
+        x = x.wrapping_mul(0x5851_F42D_4C95_7F2D).wrapping_add(1);
+        f(x >> 11)
+    })
+}
+
+macro_rules! impl_p_test {
+    ($name:ident, $state:ty, $lane:ty, $const_p:ident, $with_p:ident, [$($rounds:literal),*]) => {
+        #[test]
+        #[allow(clippy::cast_possible_truncation, trivial_numeric_casts)]
+        fn $name() {
+            let keccak = Keccak::new();
+            let state: $state = test_state(|x| x as $lane);
+            $(
+                let mut expected = state;
+                keccak.$with_p::<$rounds>(|p| p(&mut expected));
+                let mut res = state;
+                const_fn::$const_p::<$rounds>(&mut res);
+                assert_eq!(res, expected, "rounds: {}", $rounds);
+            )*
+        }
+    };
+}
+
+impl_p_test!(
+    p200,
+    State200,
+    u8,
+    p200,
+    with_p200,
+    [0, 1, 5, 12, 18, 25, 40]
+);
+impl_p_test!(p400, State400, u16, p400, with_p400, [0, 1, 5, 12, 20, 30]);
+impl_p_test!(p800, State800, u32, p800, with_p800, [0, 1, 5, 12, 22, 30]);
+impl_p_test!(
+    p1600,
+    State1600,
+    u64,
+    p1600,
+    with_p1600,
+    [0, 1, 5, 12, 24, 30, 48]
+);
+
+#[test]
+fn f1600_in_const_context() {
+    const STATE: State1600 = {
+        let mut state = [0u64; 25];
+        const_fn::f1600(&mut state);
+        state
+    };
+    let mut expected = [0u64; 25];
+    Keccak::new().with_f1600(|f| f(&mut expected));
+    assert_eq!(STATE, expected);
+}
+
+#[test]
+fn sha3_and_shake() {
+    const SHA3_224: [u8; 28] = const_fn::sha3_224(b"abc");
+    const SHA3_256: [u8; 32] = const_fn::sha3_256(b"abc");
+    const SHA3_384: [u8; 48] = const_fn::sha3_384(b"abc");
+    const SHA3_512: [u8; 64] = const_fn::sha3_512(b"abc");
+    const SHAKE128: [u8; 400] = const_fn::shake128(&[0xA3; 200]);
+    const SHAKE256: [u8; 300] = const_fn::shake256(b"");
+
+    assert_eq!(SHA3_224, fips202::sha3_224(b"abc"));
+    assert_eq!(SHA3_256, fips202::sha3_256(b"abc"));
+    assert_eq!(SHA3_384, fips202::sha3_384(b"abc"));
+    assert_eq!(SHA3_512, fips202::sha3_512(b"abc"));
+
+    let mut expected = [0u8; 400];
+    fips202::shake128(&[0xA3; 200], &mut expected);
+    assert_eq!(SHAKE128, expected);
+
+    let mut expected = [0u8; 300];
+    fips202::shake256(b"", &mut expected);
+    assert_eq!(SHAKE256, expected);
+}
+
+/// cSHAKE128 sponge with `N = ""` and `S = "Email Signature"` after absorbing
+/// the encoded prefix.
+const CSHAKE_PREFIX: ConstSponge<168, 24> = {
+    let mut sponge = ConstSponge::new();
+    sponge.absorb(left_encode(168).as_bytes());
+    sponge.absorb(left_encode(0).as_bytes());
+    sponge.absorb(left_encode(8 * 15).as_bytes());
+    sponge.absorb(b"Email Signature");
+    sponge.absorb(&[0; 168 - 21]);
+    sponge
+};
+
+#[test]
+fn precomputed_cshake_prefix() {
+    let mut sponge = CSHAKE_PREFIX.clone();
+    sponge.absorb(&[0, 1, 2, 3]);
+    sponge.absorb_last(0x04);
+    let mut out = [0u8; 32];
+    sponge.squeeze(&mut out);
+    assert_eq!(
+        out,
+        hex!("C1C36925B6409A04F1B504FCBCA9D82B4017277CB5ED2B2065FC1D3814D5AAF5")
+    );
+}
+
+const CSHAKE128: ConstSponge<168, 24> = const_fn::cshake(b"", b"Email Signature");
+const CSHAKE256: ConstSponge<136, 24> = const_fn::cshake(b"fn", &[0x5A; 300]);
+const KMAC128: ConstSponge<168, 24> = const_fn::kmac(&[0x40; 32], b"My Tagged Application");
+const KMAC256: ConstSponge<136, 24> = const_fn::kmac(&[0x40; 200], b"");
+
+#[test]
+fn const_cshake_and_kmac_prefix() {
+    assert_eq!(CSHAKE128.state(), CSHAKE_PREFIX.state());
+
+    let data = [0xC3; 300];
+    for len in [0, 1, 135, 136, 168, 300] {
+        let data = &data[..len];
+
+        let mut sponge = CSHAKE128.clone();
+        sponge.absorb(data);
+        sponge.absorb_last(0x04);
+        let mut out = [0u8; 200];
+        sponge.squeeze(&mut out);
+        let mut expected = [0u8; 200];
+        sp800_185::cshake128(data, b"", b"Email Signature", &mut expected);
+        assert_eq!(out, expected, "len: {len}");
+
+        let mut sponge = CSHAKE256.clone();
+        sponge.absorb(data);
+        sponge.absorb_last(0x04);
+        sponge.squeeze(&mut out);
+        sp800_185::cshake256(data, b"fn", &[0x5A; 300], &mut expected);
+        assert_eq!(out, expected, "len: {len}");
+
+        let mut sponge = KMAC128.clone();
+        sponge.absorb(data);
+        sponge.absorb(right_encode(8 * 32).as_bytes());
+        sponge.absorb_last(0x04);
+        let mut out = [0u8; 32];
+        sponge.squeeze(&mut out);
+        let mut expected = [0u8; 32];
+        sp800_185::kmac128(&[0x40; 32], data, b"My Tagged Application", &mut expected);
+        assert_eq!(out, expected, "len: {len}");
+
+        let mut sponge = KMAC256.clone();
+        sponge.absorb(data);
+        sponge.absorb(right_encode(0).as_bytes());
+        sponge.absorb_last(0x04);
+        let mut out = [0u8; 100];
+        sponge.squeeze(&mut out);
+        let mut expected = [0u8; 100];
+        sp800_185::kmac_xof256(&[0x40; 200], data, b"", &mut expected);
+        assert_eq!(out, expected, "len: {len}");
+    }
+}
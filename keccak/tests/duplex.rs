@@ -0,0 +1,209 @@
+//! Tests for the duplex construction and SpongeWrap.
+use core::array;
+use keccak::duplex::{Duplex, Error, SpongeWrap};
+use keccak::sponge::{Sponge, Width};
+use keccak::{Keccak, State200, State800, State1600, StateBytes};
+
+#[allow(clippy::cast_possible_truncation)]
+fn msg<const N: usize>() -> [u8; N] {
+    array::from_fn(|i| (i as u8).wrapping_mul(0x3B) ^ 0xA5)
+}
+
+/// Every duplexing output is equal to the sponge output over all previous padded inputs.
+fn check_sponge_equivalence<W: Width, const RATE: usize, const ROUNDS: usize>() {
+    let msg = msg::<256>();
+    let mut duplex = Duplex::<W, RATE, ROUNDS>::new();
+    let mut sponge_input = Vec::new();
+
+    for (i, len) in [0, 1, RATE / 2, RATE - 1, 3].into_iter().enumerate() {
+        let input = &msg[i..][..len];
+        let suffix = [0x01, 0x06, 0x0B, 0x7F, 0x02][i];
+
+        let mut out = [0u8; RATE];
+        duplex.duplexing_with_suffix(input, suffix, &mut out);
+
+        let mut expected = [0u8; RATE];
+        let mut sponge = Sponge::<W, RATE, ROUNDS>::new();
+        sponge.absorb(&sponge_input);
+        sponge.absorb(input);
+        sponge.absorb_last(suffix);
+        sponge.squeeze(&mut expected);
+        assert_eq!(out, expected);
+
+        // Append the padded block to the sponge input
+        let mut block = [0u8; RATE];
+        block[..len].copy_from_slice(input);
+        block[len] ^= suffix;
+        block[RATE - 1] ^= 0x80;
+        sponge_input.extend_from_slice(&block);
+    }
+}
+
+#[test]
+fn duplex_sponge_equivalence() {
+    check_sponge_equivalence::<State200, 18, 18>();
+    check_sponge_equivalence::<State800, 68, 12>();
+    check_sponge_equivalence::<State1600, 136, 24>();
+    check_sponge_equivalence::<State1600, 167, 12>();
+}
+
+#[test]
+fn duplex_overwrite() {
+    const RATE: usize = 100;
+    let msg = msg::<RATE>();
+    let p = State1600::get_p::<12>(&Keccak::new());
+
+    let mut duplex = Duplex::<State1600, RATE, 12>::new_overwrite();
+    let mut state = State1600::default();
+    for len in [0, 10, RATE - 1, 5] {
+        let mut out = [0u8; RATE];
+        duplex.duplexing(&msg[..len], &mut out);
+
+        let mut block = [0u8; RATE];
+        block[..len].copy_from_slice(&msg[..len]);
+        block[len] ^= 0x01;
+        block[RATE - 1] ^= 0x80;
+        state.overwrite_bytes(&block, 0);
+        p(&mut state);
+        let mut expected = [0u8; RATE];
+        state.extract_bytes(&mut expected, 0);
+        assert_eq!(out, expected);
+    }
+}
+
+#[test]
+fn duplex_overwrite_full() {
+    const RATE: usize = 100;
+    let msg = msg::<200>();
+    let p = State1600::get_p::<12>(&Keccak::new());
+
+    let mut duplex = Duplex::<State1600, RATE, 12>::new();
+    duplex.duplexing(&msg[..50], &mut []);
+    for len in [0, 10, RATE, 199, 5] {
+        let mut out = [0u8; RATE];
+        duplex.duplexing_overwrite_full(&msg[..len], &mut out);
+
+        // The previous state is fully replaced by the padded input
+        let mut state = State1600::default();
+        state.overwrite_bytes(&msg[..len], 0);
+        state.add_bytes(&[0x01], len);
+        state.add_bytes(&[0x80], 199);
+        p(&mut state);
+        let mut expected = [0u8; RATE];
+        state.extract_bytes(&mut expected, 0);
+        assert_eq!(out, expected, "len: {len}");
+    }
+}
+
+#[test]
+#[should_panic]
+fn duplex_overwrite_full_input_too_long() {
+    let mut duplex = Duplex::<State200, 18, 18>::new();
+    duplex.duplexing_overwrite_full(&[0u8; 25], &mut []);
+}
+
+#[test]
+#[should_panic]
+fn duplex_input_too_long() {
+    let mut duplex = Duplex::<State1600, 136, 24>::new();
+    duplex.duplexing(&[0u8; 136], &mut []);
+}
+
+#[test]
+#[should_panic]
+fn duplex_suffix_does_not_fit() {
+    let mut duplex = Duplex::<State1600, 136, 24>::new();
+    duplex.duplexing_with_suffix(&[0u8; 135], 0x80, &mut []);
+}
+
+fn check_wrap<W: Width, const RATE: usize, const ROUNDS: usize>() {
+    let key = msg::<40>();
+    let data = msg::<300>();
+
+    let mut enc = SpongeWrap::<W, RATE, ROUNDS>::new(&key);
+    let mut dec = SpongeWrap::<W, RATE, ROUNDS>::new(&key);
+    let mut other = SpongeWrap::<W, RATE, ROUNDS>::new(&key[1..]);
+
+    let lens = [0, 1, RATE - 2, RATE - 1, RATE, 2 * RATE + 3, 300];
+    for (i, (header_len, data_len)) in lens.into_iter().zip(lens.into_iter().rev()).enumerate() {
+        let header = &data[i..][..header_len.min(300 - i)];
+        let plaintext = &data[..data_len.min(300)];
+        let data_len = plaintext.len();
+
+        let mut buf = plaintext.to_vec();
+        let mut tag = [0u8; 50];
+        enc.wrap(header, &mut buf, &mut tag);
+        if data_len > 8 {
+            assert_ne!(buf, plaintext);
+        }
+
+        let mut buf2 = buf.clone();
+        let mut other_tag = [0u8; 50];
+        other.wrap(header, &mut buf2, &mut other_tag);
+        assert_ne!(tag, other_tag);
+
+        dec.unwrap(header, &mut buf, &tag).expect("valid tag");
+        assert_eq!(buf, plaintext);
+    }
+
+    // Ciphertext modification
+    let mut buf = data.to_vec();
+    let mut tag = [0u8; 16];
+    enc.clone().wrap(b"header", &mut buf, &mut tag);
+    buf[123] ^= 1;
+    let mut dec2 = dec.clone();
+    assert_eq!(dec2.unwrap(b"header", &mut buf, &tag), Err(Error));
+    assert!(buf.iter().all(|&b| b == 0));
+
+    // Header modification
+    let mut buf = data.to_vec();
+    enc.clone().wrap(b"header", &mut buf, &mut tag);
+    assert_eq!(dec.clone().unwrap(b"Header", &mut buf, &tag), Err(Error));
+
+    // Tag modification
+    let mut buf = data.to_vec();
+    enc.clone().wrap(b"header", &mut buf, &mut tag);
+    tag[15] ^= 0x80;
+    assert_eq!(dec.clone().unwrap(b"header", &mut buf, &tag), Err(Error));
+
+    // Empty and truncated tags
+    let mut buf = data.to_vec();
+    enc.clone().wrap(b"header", &mut buf, &mut tag);
+    for len in [0, 1, SpongeWrap::<W, RATE, ROUNDS>::MIN_TAG_LEN - 1] {
+        let mut buf2 = buf.clone();
+        assert_eq!(
+            dec.clone().unwrap(b"header", &mut buf2, &tag[..len]),
+            Err(Error)
+        );
+        assert_eq!(buf2, buf);
+    }
+    let mut buf2 = buf.clone();
+    dec.clone()
+        .unwrap(b"header", &mut buf2, &tag)
+        .expect("valid tag");
+}
+
+#[test]
+fn spongewrap_roundtrip() {
+    check_wrap::<State200, 10, 18>();
+    check_wrap::<State800, 40, 22>();
+    check_wrap::<State1600, 136, 24>();
+    check_wrap::<State1600, 168, 12>();
+}
+
+#[test]
+#[should_panic]
+fn spongewrap_short_tag() {
+    let mut enc = SpongeWrap::<State1600, 136, 24>::new(b"key");
+    enc.wrap(b"header", &mut [0u8; 10], &mut []);
+}
+
+#[test]
+#[should_panic]
+fn spongewrap_poisoned() {
+    let key = [0u8; 16];
+    let mut sw = SpongeWrap::<State1600, 136, 24>::new(&key);
+    let mut buf = [1u8; 10];
+    let _ = sw.unwrap(b"", &mut buf, &[0u8; 16]);
+    sw.wrap(b"", &mut buf, &mut [0u8; 16]);
+}
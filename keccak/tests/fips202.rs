@@ -0,0 +1,114 @@
+//! FIPS 202 test vectors.
+use core::array;
+use hex_literal::hex;
+use keccak::fips202::*;
+
+#[allow(clippy::cast_possible_truncation)]
+fn msg() -> [u8; 1000] {
+    array::from_fn(|i| (i as u8).wrapping_mul(0x3B) ^ 0xA5)
+}
+
+macro_rules! test_sha3 {
+    ($test_name:ident, $name:ident, $fn_name:ident, $empty:literal, $abc:literal, $long:literal) => {
+        #[test]
+        fn $test_name() {
+            assert_eq!($fn_name(b""), hex!($empty));
+            assert_eq!($fn_name(b"abc"), hex!($abc));
+
+            let msg = msg();
+            assert_eq!($fn_name(&msg), hex!($long));
+
+            // Incremental updates with different chunk sizes
+            for chunk_len in [1, 7, 64, $name::RATE - 1, $name::RATE, $name::RATE + 1] {
+                let mut hasher = $name::new();
+                for chunk in msg.chunks(chunk_len) {
+                    hasher.update(chunk);
+                }
+                assert_eq!(hasher.finalize(), hex!($long));
+            }
+        }
+    };
+}
+
+test_sha3!(
+    sha3_224_vectors,
+    Sha3_224,
+    sha3_224,
+    "6b4e03423667dbb73b6e15454f0eb1abd4597f9a1b078e3f5b5a6bc7",
+    "e642824c3f8cf24ad09234ee7d3c766fc9a3a5168d0c94ad73b46fdf",
+    "47ddbe418f7d4fd049488cbb37cbe989344e4fc523656f78cfb5e9dc"
+);
+
+test_sha3!(
+    sha3_256_vectors,
+    Sha3_256,
+    sha3_256,
+    "a7ffc6f8bf1ed76651c14756a061d662f580ff4de43b49fa82d80a4b80f8434a",
+    "3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532",
+    "598dd25f91a42881d4da76bfaae2a23fc28afdf1b26f5d1703b3927614569e3f"
+);
+
+test_sha3!(
+    sha3_384_vectors,
+    Sha3_384,
+    sha3_384,
+    "0c63a75b845e4f7d01107d852e4c2485c51a50aaaa94fc61995e71bbee983a2ac3713831264adb47fb6bd1e058d5f004",
+    "ec01498288516fc926459f58e2c6ad8df9b473cb0fc08c2596da7cf0e49be4b298d88cea927ac7f539f1edf228376d25",
+    "f21a9792d29cc9f3087fe86f4c0c006d249241ff67afb3898857586b07305961aee679870d2a68823b64ba8d0a369608"
+);
+
+test_sha3!(
+    sha3_512_vectors,
+    Sha3_512,
+    sha3_512,
+    "a69f73cca23a9ac5c8b567dc185a756e97c982164fe25859e0d1dcc1475c80a615b2123af1f5f94c11e3e9402c3ac558f500199d95b6d3e301758586281dcd26",
+    "b751850b1a57168a5693cd924b6b096e08f621827444f70d884f5d0240d2712e10e116e9192af3c91a7ec57647e3934057340b4cf408d5a56592f8274eec53f0",
+    "0b0cb5bc6eafbed07696bd22d5325791f24107cf4dc7e2f6c9311f598d277afeebd3814e4cbd6136eb3406c8817b667ff281c28de318af65b534bff101a43f1d"
+);
+
+macro_rules! test_shake {
+    ($test_name:ident, $name:ident, $fn_name:ident, $empty:literal, $long:literal) => {
+        #[test]
+        fn $test_name() {
+            let mut out = [0u8; 32];
+            $fn_name(b"", &mut out);
+            assert_eq!(out, hex!($empty));
+
+            let msg = msg();
+            let expected = hex!($long);
+            let mut out = [0u8; 300];
+            $fn_name(&msg, &mut out);
+            assert_eq!(out, expected);
+
+            // Incremental updates and multiple reads with different chunk sizes
+            for chunk_len in [1, 7, 64, $name::RATE - 1, $name::RATE, $name::RATE + 1] {
+                let mut hasher = $name::new();
+                for chunk in msg.chunks(chunk_len) {
+                    hasher.update(chunk);
+                }
+                let mut reader = hasher.finalize_xof();
+                let mut out = [0u8; 300];
+                for chunk in out.chunks_mut(chunk_len) {
+                    reader.read(chunk);
+                }
+                assert_eq!(out, expected);
+            }
+        }
+    };
+}
+
+test_shake!(
+    shake128_vectors,
+    Shake128,
+    shake128,
+    "7f9c2ba4e88f827d616045507605853ed73b8093f6efbc88eb1a6eacfa66ef26",
+    "7acbc22a136a8371fd7b918c1b807b28fe35258ee10846e6ceb733c48a0a718b95c32d55131ab2a3a367f95668a791da291e04cc2787f862ed5215a38223312f59e4d584500eec5d659317312ea9c859a2824fe29ba23af9c5bcf16fc90e1f295939b592ac4ccc82b2d85faab0757e13388f6ec295957fd7fb594f8a3945b67e5fab54b0adf6440f7f4758a6673e8b1be9aa9afa50d7e124b236168c5f9c0eb9ac30e680b88a3693e694d606de15e094d293c966a7264ad071a442ddfc115ccf1b58d41ada4ffe3ef287b22eb4fa6de8ec8bd0d991418a068358f0f4826f671225e5af512a7a1149d59a6f57ab1075a08f5ef0c12ade91c00af2a595cb3b67971bb99a71c0ab15942c7a317a0da5b5f7d8ecd79e06e0d005a13266951aa050966f8540317ba594663136f5e6"
+);
+
+test_shake!(
+    shake256_vectors,
+    Shake256,
+    shake256,
+    "46b9dd2b0ba88d13233b3feb743eeb243fcd52ea62b81b82b50c27646ed5762f",
+    "a14deea76a72e809ea72de8204013cddca5530439893e87f7842442b8490feea579bf06411a9e3fcabb7bcb0954ad6e318a14331c34f8949d707ccaf71342734cf7579227cbf5dfe02636d853e7b34433bda7de22b3f04a5ddd5b9b920dabfc1cf254d2eb316093508555cdb98f82fd3984ef05c103d837ef12fead579505f97ea7ccee9c958e5ff6a060a9347332359fb2c0de71aabe1c823fc6fbc3260b158f6d84923aa3d0cf665a6a6f476012e875a6f53c8e31890d75ba701adec9f54d5bd34889e5a27386c2ef70d1a269d41b5d962ea337a659a10e03d05d71399cf9e7c1e06e44fcae6601d2af3f607f271830c0d41564ac8d868bf25280466f12d9de2b25004aa23ff9f19f6f399fe3f93c5f1b51cf57f9f3ab4344b6fec2c10c09ee845f496eb30366eae2aea53"
+);
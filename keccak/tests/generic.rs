@@ -0,0 +1,207 @@
+//! Tests of the generic Keccak-p over user-defined lane types.
+use core::ops::{BitAnd, BitAndAssign, BitOr, BitXor, BitXorAssign, Not};
+use keccak::{
+    Keccak, PLEN, State800,
+    generic::{LaneSize, keccak_f_round_count},
+    steps,
+};
+
+/// Lane of `W` bits stored in the lower bits of `u8`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct Small<const W: u32>(u8);
+
+impl<const W: u32> Small<W> {
+    const MASK: u8 = u8::MAX >> (8 - W);
+}
+
+/// Four 32-bit lanes processed at once.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct X4([u32; 4]);
+
+macro_rules! impl_ops {
+    ($type:ident $(<const $w:ident: u32>)?, $map:ident) => {
+        impl$(<const $w: u32>)? BitAnd for $type$(<$w>)? {
+            type Output = Self;
+            fn bitand(self, rhs: Self) -> Self {
+                self.$map(rhs, |a, b| a & b)
+            }
+        }
+
+        impl$(<const $w: u32>)? BitAndAssign for $type$(<$w>)? {
+            fn bitand_assign(&mut self, rhs: Self) {
+                *self = *self & rhs;
+            }
+        }
+
+        impl$(<const $w: u32>)? BitOr for $type$(<$w>)? {
+            type Output = Self;
+            fn bitor(self, rhs: Self) -> Self {
+                self.$map(rhs, |a, b| a | b)
+            }
+        }
+
+        impl$(<const $w: u32>)? BitXor for $type$(<$w>)? {
+            type Output = Self;
+            fn bitxor(self, rhs: Self) -> Self {
+                self.$map(rhs, |a, b| a ^ b)
+            }
+        }
+
+        impl$(<const $w: u32>)? BitXorAssign for $type$(<$w>)? {
+            fn bitxor_assign(&mut self, rhs: Self) {
+                *self = *self ^ rhs;
+            }
+        }
+    };
+}
+
+impl<const W: u32> Small<W> {
+    fn zip(self, rhs: Self, f: impl Fn(u8, u8) -> u8) -> Self {
+        Self(f(self.0, rhs.0) & Self::MASK)
+    }
+}
+
+impl X4 {
+    fn zip(self, rhs: Self, f: impl Fn(u32, u32) -> u32) -> Self {
+        Self(core::array::from_fn(|i| f(self.0[i], rhs.0[i])))
+    }
+}
+
+impl_ops!(Small<const W: u32>, zip);
+impl_ops!(X4, zip);
+
+impl<const W: u32> Not for Small<W> {
+    type Output = Self;
+    fn not(self) -> Self {
+        Self(!self.0 & Self::MASK)
+    }
+}
+
+impl Not for X4 {
+    type Output = Self;
+    fn not(self) -> Self {
+        Self(self.0.map(|x| !x))
+    }
+}
+
+impl<const W: u32> LaneSize for Small<W> {
+    const BITS: u32 = W;
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn truncate_rc(rc: u64) -> Self {
+        Self(rc as u8 & Self::MASK)
+    }
+
+    fn rotate_left(self, n: u32) -> Self {
+        if n == 0 {
+            return self;
+        }
+        Self(((self.0 << n) | (self.0 >> (W - n))) & Self::MASK)
+    }
+}
+
+impl LaneSize for X4 {
+    const BITS: u32 = 32;
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn truncate_rc(rc: u64) -> Self {
+        Self([rc as u32; 4])
+    }
+
+    fn rotate_left(self, n: u32) -> Self {
+        Self(self.0.map(|x| x.rotate_left(n)))
+    }
+}
+
+/// State with lanes equal to their indices modulo `2^W`.
+#[allow(clippy::cast_possible_truncation)]
+fn index_state<const W: u32>() -> [Small<W>; PLEN] {
+    core::array::from_fn(|i| Small(i as u8 & Small::<W>::MASK))
+}
+
+#[test]
+fn small_lanes() {
+    assert_eq!(keccak_f_round_count::<Small<1>>(), 12);
+    assert_eq!(keccak_f_round_count::<Small<2>>(), 14);
+    assert_eq!(keccak_f_round_count::<Small<4>>(), 16);
+
+    let mut state = index_state::<1>();
+    keccak::generic::keccak_p::<_, 12>(&mut state);
+    let expected = [
+        1, 1, 1, 1, 1, 1, 1, 0, 1, 1, 1, 0, 1, 1, 1, 0, 1, 0, 0, 0, 0, 0, 0, 0, 1,
+    ];
+    assert_eq!(state, expected.map(Small));
+
+    let mut state = index_state::<2>();
+    keccak::generic::keccak_p::<_, 14>(&mut state);
+    let expected = [
+        3, 3, 2, 3, 2, 2, 1, 1, 2, 0, 3, 1, 0, 3, 3, 3, 0, 1, 2, 1, 3, 1, 0, 1, 2,
+    ];
+    assert_eq!(state, expected.map(Small));
+
+    let mut state = index_state::<4>();
+    keccak::generic::keccak_p::<_, 16>(&mut state);
+    let expected = [
+        15, 12, 10, 6, 9, 3, 8, 12, 0, 8, 4, 5, 9, 8, 8, 12, 14, 6, 13, 9, 7, 4, 2, 2, 3,
+    ];
+    assert_eq!(state, expected.map(Small));
+
+    // Keccak-p[100, 20] starts from the round index -4
+    let mut state = index_state::<4>();
+    Keccak::new().with_p::<Small<4>, 20>(|p| p(&mut state));
+    let expected = [
+        5, 1, 10, 1, 15, 15, 8, 1, 11, 14, 3, 14, 7, 3, 1, 10, 7, 13, 11, 14, 5, 12, 7, 9, 6,
+    ];
+    assert_eq!(state, expected.map(Small));
+}
+
+#[test]
+fn small_lane_steps() {
+    let mut expected = index_state::<4>();
+    keccak::generic::keccak_p::<_, 16>(&mut expected);
+
+    let mut state = index_state::<4>();
+    for ir in 0..16 {
+        steps::round(&mut state, ir);
+    }
+    assert_eq!(state, expected);
+}
+
+#[test]
+#[allow(clippy::cast_possible_truncation)]
+fn packed_lanes() {
+    let keccak = Keccak::new();
+    let states: [State800; 4] = core::array::from_fn(|i| {
+        core::array::from_fn(|j| (0x9E37_79B9u32 ^ (j as u32)).wrapping_mul(i as u32 + 1))
+    });
+
+    let mut packed: [X4; PLEN] = core::array::from_fn(|j| X4(states.map(|s| s[j])));
+    keccak.with_p::<X4, 22>(|p| p(&mut packed));
+    for (i, mut state) in states.into_iter().enumerate() {
+        keccak.with_f800(|f| f(&mut state));
+        assert_eq!(packed.map(|lane| lane.0[i]), state);
+    }
+}
+
+#[test]
+fn primitive_lanes() {
+    let keccak = Keccak::new();
+
+    let mut state = [0u64; PLEN];
+    keccak.with_p::<u64, 24>(|p| p(&mut state));
+    let mut expected = [0u64; PLEN];
+    keccak.with_f1600(|f| f(&mut expected));
+    assert_eq!(state, expected);
+
+    let mut state = [1u8; PLEN];
+    keccak.with_p::<u8, 12>(|p| p(&mut state));
+    let mut expected = [1u8; PLEN];
+    keccak::generic::keccak_p::<u8, 12>(&mut expected);
+    assert_eq!(state, expected);
+
+    let mut state = [0x1234u16; PLEN];
+    keccak.with_p::<u16, 20>(|p| p(&mut state));
+    let mut expected = [0x1234u16; PLEN];
+    keccak.with_f400(|f| f(&mut expected));
+    assert_eq!(state, expected);
+}
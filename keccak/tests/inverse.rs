@@ -0,0 +1,73 @@
+//! Round-trip tests of the inverse Keccak-p permutations.
+use keccak::{Keccak, State200, State400, State800, State1600, inverse};
+
+fn test_states<T>(f: impl Fn(u64) -> T) -> [[T; 25]; 3] {
+    let mut x = 0xFEDC_BA98_7654_3210u64;
+    core::array::from_fn(|_| {
+        core::array::from_fn(|_| {
+            x = x.wrapping_mul(0x5851_F42D_4C95_7F2D).wrapping_add(1);
+            f(x >> 7)
+        })
+    })
+}
+
+macro_rules! impl_round_trip_test {
+    (
+        $name:ident, $state:ty, $lane:ty, $with_p:ident, $inv_p:ident, $with_f:ident, $inv_f:ident,
+        [$($rounds:literal)*]
+    ) => {
+        #[test]
+        #[allow(clippy::cast_possible_truncation, trivial_numeric_casts)]
+        fn $name() {
+            let keccak = Keccak::new();
+            let states: [$state; 3] = test_states(|x| x as $lane);
+            let states = [[0; 25], [<$lane>::MAX; 25], states[0], states[1], states[2]];
+            for state in states {
+                $(
+                    let mut s = state;
+                    keccak.$with_p::<$rounds>(|p| p(&mut s));
+                    inverse::$inv_p::<$rounds>(&mut s);
+                    assert_eq!(s, state, "rounds: {}", $rounds);
+
+                    let mut s = state;
+                    inverse::$inv_p::<$rounds>(&mut s);
+                    keccak.$with_p::<$rounds>(|p| p(&mut s));
+                    assert_eq!(s, state, "rounds: {}", $rounds);
+                )*
+
+                let mut s = state;
+                keccak.$with_f(|f| f(&mut s));
+                inverse::$inv_f(&mut s);
+                assert_eq!(s, state);
+            }
+        }
+    };
+}
+
+impl_round_trip_test!(
+    p200, State200, u8, with_p200, inverse_p200, with_f200, inverse_f200,
+    [0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 24]
+);
+impl_round_trip_test!(
+    p400, State400, u16, with_p400, inverse_p400, with_f400, inverse_f400,
+    [0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 24]
+);
+impl_round_trip_test!(
+    p800, State800, u32, with_p800, inverse_p800, with_f800, inverse_f800,
+    [0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 30]
+);
+impl_round_trip_test!(
+    p1600, State1600, u64, with_p1600, inverse_p1600, with_f1600, inverse_f1600,
+    [0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 30]
+);
+
+#[test]
+fn inverse_in_const_context() {
+    const STATE: State1600 = {
+        let mut state = [0u64; 25];
+        keccak::const_fn::f1600(&mut state);
+        inverse::inverse_f1600(&mut state);
+        state
+    };
+    assert_eq!(STATE, [0u64; 25]);
+}
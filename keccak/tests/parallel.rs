@@ -2,7 +2,7 @@
 #![cfg(feature = "parallel")]
 use core::array;
 use hybrid_array::Array;
-use keccak::{Backend, BackendClosure, State1600};
+use keccak::{Backend, BackendClosure, State200, State400, State800, State1600};
 
 const N: usize = 50;
 
@@ -37,3 +37,41 @@ fn keccak_par_f1600() {
 
     keccak::Keccak::new().with_backend(Closure);
 }
+
+macro_rules! impl_test {
+    ($name:ident, $state:ty, $lane:ty, $get_f:ident, $get_par_f:ident) => {
+        /// Test that we get the same result for scalar and parallel functions.
+        #[test]
+        fn $name() {
+            struct Closure;
+
+            impl BackendClosure for Closure {
+                #[allow(clippy::cast_possible_truncation)]
+                fn call_once<B: Backend>(self) {
+                    let f = B::$get_f();
+                    let par_f = B::$get_par_f();
+
+                    let mut buf: [$state; N] =
+                        array::from_fn(|i| array::from_fn(|j| (i * 31 + j) as $lane));
+                    let expected: [$state; N] = buf.map(|mut s| {
+                        f(&mut s);
+                        s
+                    });
+
+                    let (chunks, tail) = Array::slice_as_chunks_mut(&mut buf[..]);
+
+                    chunks.iter_mut().for_each(par_f);
+                    tail.iter_mut().for_each(f);
+
+                    assert_eq!(buf, expected);
+                }
+            }
+
+            keccak::Keccak::new().with_backend(Closure);
+        }
+    };
+}
+
+impl_test!(keccak_par_f200, State200, u8, get_f200, get_par_f200);
+impl_test!(keccak_par_f400, State400, u16, get_f400, get_par_f400);
+impl_test!(keccak_par_f800, State800, u32, get_f800, get_par_f800);
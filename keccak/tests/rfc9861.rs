@@ -0,0 +1,323 @@
+//! RFC 9861 test vectors.
+//!
+//! <https://www.rfc-editor.org/rfc/rfc9861#name-test-vectors>
+use hex_literal::hex;
+use keccak::rfc9861::*;
+use keccak::sakura::length_encode;
+
+/// Pattern used by the RFC test vectors.
+#[allow(clippy::cast_possible_truncation)]
+fn ptn(len: usize) -> Vec<u8> {
+    (0..len).map(|i| (i % 251) as u8).collect()
+}
+
+#[test]
+fn turbo_shake128_vectors() {
+    let mut out = [0u8; 64];
+    turbo_shake128(b"", 0x1F, &mut out);
+    assert_eq!(
+        out,
+        hex!(
+            "1E415F1C5983AFF2169217277D17BB538CD945A397DDEC541F1CE41AF2C1B74C"
+            "3E8CCAE2A4DAE56C84A04C2385C03C15E8193BDF58737363321691C05462C8DF"
+        )
+    );
+
+    let mut out = [0u8; 32];
+    turbo_shake128(&ptn(17), 0x1F, &mut out);
+    assert_eq!(
+        out,
+        hex!("9C97D036A3BAC819DB70EDE0CA554EC6E4C2A1A4FFBFD9EC269CA6A111161233")
+    );
+
+    turbo_shake128(&ptn(17 * 17 * 17), 0x1F, &mut out);
+    assert_eq!(
+        out,
+        hex!("D4976EB56BCF118520582B709F73E1D6853E001FDAF80E1B13E0D0599D5FB372")
+    );
+
+    turbo_shake128(&[0xFF; 3], 0x01, &mut out);
+    assert_eq!(
+        out,
+        hex!("BF323F940494E88EE1C540FE660BE8A0C93F43D15EC006998462FA994EED5DAB")
+    );
+
+    turbo_shake128(&[0xFF], 0x06, &mut out);
+    assert_eq!(
+        out,
+        hex!("8EC9C66465ED0D4A6C35D13506718D687A25CB05C74CCA1E42501ABD83874A67")
+    );
+}
+
+#[test]
+fn turbo_shake256_vectors() {
+    let mut out = [0u8; 64];
+    turbo_shake256(b"", 0x1F, &mut out);
+    assert_eq!(
+        out,
+        hex!(
+            "367A329DAFEA871C7802EC67F905AE13C57695DC2C6663C61035F59A18F8E7DB"
+            "11EDC0E12E91EA60EB6B32DF06DD7F002FBAFABB6E13EC1CC20D995547600DB0"
+        )
+    );
+
+    // Incremental updates and reads
+    let mut hasher = TurboShake256::default();
+    for chunk in ptn(17 * 17).chunks(10) {
+        hasher.update(chunk);
+    }
+    let mut reader = hasher.finalize_xof();
+    for chunk in out.chunks_mut(7) {
+        reader.read(chunk);
+    }
+    assert_eq!(
+        out,
+        hex!(
+            "66B810DB8E90780424C0847372FDC95710882FDE31C6DF75BEB9D4CD9305CFCA"
+            "E35E7B83E8B7E6EB4B78605880116316FE2C078A09B94AD7B8213C0A738B65C0"
+        )
+    );
+}
+
+#[test]
+#[should_panic]
+fn turbo_shake_invalid_domain() {
+    let _ = TurboShake128::new(0x80);
+}
+
+#[test]
+fn kt128_vectors() {
+    let msg_vectors = [
+        hex!("2BDA92450E8B147F8A7CB629E784A058EFCA7CF7D8218E02D345DFAA65244A1F"),
+        hex!("6BF75FA2239198DB4772E36478F8E19B0F371205F6A9A93A273F51DF37122888"),
+        hex!("0C315EBCDEDBF61426DE7DCF8FB725D1E74675D7F5327A5067F367B108ECB67C"),
+        hex!("CB552E2EC77D9910701D578B457DDF772C12E322E4EE7FE417F92C758F0D59D0"),
+        hex!("8701045E22205345FF4DDA05555CBB5C3AF1A771C2B89BAEF37DB43D9998B9FE"),
+    ];
+    let mut out = [0u8; 32];
+    let mut len = 1;
+    for expected in msg_vectors {
+        kt128(&ptn(len), b"", &mut out);
+        assert_eq!(out, expected, "length: {len}");
+        len *= 17;
+    }
+
+    let vectors: [(&[u8], &[u8], [u8; 32]); 7] = [
+        (
+            b"",
+            &ptn(1),
+            hex!("FAB658DB63E94A246188BF7AF69A133045F46EE984C56E3C3328CAAF1AA1A583"),
+        ),
+        (
+            &[0xFF],
+            &ptn(41),
+            hex!("D848C5068CED736F4462159B9867FD4C20B808ACC3D5BC48E0B06BA0A3762EC4"),
+        ),
+        (
+            &[0xFF; 3],
+            &ptn(41 * 41),
+            hex!("C389E5009AE57120854C2E8C64670AC01358CF4C1BAF89447A724234DC7CED74"),
+        ),
+        (
+            &ptn(8191),
+            b"",
+            hex!("1B577636F723643E990CC7D6A659837436FD6A103626600EB8301CD1DBE553D6"),
+        ),
+        (
+            &ptn(8192),
+            b"",
+            hex!("48F256F6772F9EDFB6A8B661EC92DC93B95EBD05A08A17B39AE3490870C926C3"),
+        ),
+        (
+            &ptn(8192),
+            &ptn(8189),
+            hex!("3ED12F70FB05DDB58689510AB3E4D23C6C6033849AA01E1D8C220A297FEDCD0B"),
+        ),
+        (
+            &ptn(8192),
+            &ptn(8190),
+            hex!("6A7C1B6A5CD0D8C9CA943A4A216CC64604559A2EA45F78570A15253D67BA00AE"),
+        ),
+    ];
+    for (msg, customization, expected) in vectors {
+        kt128(msg, customization, &mut out);
+        assert_eq!(out, expected);
+    }
+}
+
+#[test]
+fn kt256_vectors() {
+    let msg_vectors = [
+        hex!(
+            "0D005A194085360217128CF17F91E1F71314EFA5564539D444912E3437EFA17F"
+            "82DB6F6FFE76E781EAA068BCE01F2BBF81EACB983D7230F2FB02834A21B1DDD0"
+        ),
+        hex!(
+            "1BA3C02B1FC514474F06C8979978A9056C8483F4A1B63D0DCCEFE3A28A2F323E"
+            "1CDCCA40EBF006AC76EF0397152346837B1277D3E7FAA9C9653B19075098527B"
+        ),
+        hex!(
+            "DE8CCBC63E0F133EBB4416814D4C66F691BBF8B6A61EC0A7700F836B086CB029"
+            "D54F12AC7159472C72DB118C35B4E6AA213C6562CAAA9DCC518959E69B10F3BA"
+        ),
+        hex!(
+            "647EFB49FE9D717500171B41E7F11BD491544443209997CE1C2530D15EB1FFBB"
+            "598935EF954528FFC152B1E4D731EE2683680674365CD191D562BAE753B84AA5"
+        ),
+        hex!(
+            "B06275D284CD1CF205BCBE57DCCD3EC1FF6686E3ED15776383E1F2FA3C6AC8F0"
+            "8BF8A162829DB1A44B2A43FF83DD89C3CF1CEB61EDE659766D5CCF817A62BA8D"
+        ),
+    ];
+    let mut out = [0u8; 64];
+    let mut len = 1;
+    for expected in msg_vectors {
+        kt256(&ptn(len), b"", &mut out);
+        assert_eq!(out, expected, "length: {len}");
+        len *= 17;
+    }
+
+    let vectors: [(&[u8], &[u8], [u8; 64]); 4] = [
+        (
+            b"",
+            &ptn(1),
+            hex!(
+                "9280F5CC39B54A5A594EC63DE0BB99371E4609D44BF845C2F5B8C316D72B1598"
+                "11F748F23E3FABBE5C3226EC96C62186DF2D33E9DF74C5069CEECBB4DD10EFF6"
+            ),
+        ),
+        (
+            &[0xFF; 3],
+            &ptn(41 * 41),
+            hex!(
+                "3B48667A5051C5966C53C5D42B95DE451E05584E7806E2FB765EDA959074172C"
+                "B438A9E91DDE337C98E9C41BED94C4E0AEF431D0B64EF2324F7932CAA6F54969"
+            ),
+        ),
+        (
+            &ptn(8192),
+            b"",
+            hex!(
+                "C6EE8E2AD3200C018AC87AAA031CDAC22121B412D07DC6E0DCCBB53423747E9A"
+                "1C18834D99DF596CF0CF4B8DFAFB7BF02D139D0C9035725ADC1A01B7230A41FA"
+            ),
+        ),
+        (
+            &ptn(8192),
+            &ptn(8190),
+            hex!(
+                "F4B5908B929FFE01E0F79EC2F21243D41A396B2E7303A6AF1D6399CD6C7A0A2D"
+                "D7C4F607E8277F9C9B1CB4AB9DDC59D4B92D1FC7558441F1832C3279A4241B8B"
+            ),
+        ),
+    ];
+    for (msg, customization, expected) in vectors {
+        kt256(msg, customization, &mut out);
+        assert_eq!(out, expected);
+    }
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn data100k() -> Vec<u8> {
+    (0..100_000usize)
+        .map(|i| ((i * 0x3B) ^ 0xA5) as u8)
+        .collect()
+}
+
+#[test]
+fn kt_incremental() {
+    let data = data100k();
+    let expected128 = hex!("F1B96ED06ABB4D8B8B968FF3C24B4A350BC8D3B111239A416096B165E38B6337");
+    let expected256 = hex!(
+        "E707F209C0A24EA416906AE3911ED8560C721E1EFF8069DA2954F5C09B06749C"
+        "AB1402CBD762F0DFA2155ECFAC3AC10BDEF3F8AA3DD6939007A3EE2E30FC640A"
+    );
+
+    for chunk_size in [1000, 8191, 8192, 8193, 40_000, 100_000] {
+        let mut hasher = Kt128::new();
+        for chunk in data.chunks(chunk_size) {
+            hasher.update(chunk);
+        }
+        let mut out = [0u8; 32];
+        hasher.finalize_xof(b"cust").read(&mut out);
+        assert_eq!(out, expected128, "chunk: {chunk_size}");
+
+        let mut hasher = Kt256::new();
+        for chunk in data.chunks(chunk_size) {
+            hasher.update(chunk);
+        }
+        let mut out = [0u8; 64];
+        hasher.finalize_xof(b"cust").read(&mut out);
+        assert_eq!(out, expected256, "chunk: {chunk_size}");
+    }
+}
+
+/// Non-repeating test data of `len` bytes derived from SHAKE128.
+fn shake_data(len: usize) -> Vec<u8> {
+    let mut data = vec![0u8; len];
+    keccak::fips202::shake128(b"keccak rayon test data", &mut data);
+    data
+}
+
+/// Run `f` on a new rayon thread pool with `threads` threads.
+#[cfg(feature = "rayon")]
+fn with_threads<T: Send>(threads: usize, f: impl FnOnce() -> T + Send) -> T {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()
+        .expect("thread pool")
+        .install(f)
+}
+
+/// Straightforward KT128 which builds the final node in memory from TurboSHAKE128 calls.
+fn kt128_reference(data: &[u8], customization: &[u8], out: &mut [u8]) {
+    let mut s = [data, customization].concat();
+    length_encode(customization.len() as u64, |x| s.extend_from_slice(x));
+    if s.len() <= 8192 {
+        turbo_shake128(&s, 0x07, out);
+        return;
+    }
+
+    let mut node = s[..8192].to_vec();
+    node.extend_from_slice(&[0x03, 0, 0, 0, 0, 0, 0, 0]);
+    let chunks = s[8192..].chunks(8192);
+    let n = chunks.len();
+    for chunk in chunks {
+        let mut cv = [0u8; 32];
+        turbo_shake128(chunk, 0x0B, &mut cv);
+        node.extend_from_slice(&cv);
+    }
+    length_encode(n as u64, |x| node.extend_from_slice(x));
+    node.extend_from_slice(&[0xFF, 0xFF]);
+    turbo_shake128(&node, 0x06, out);
+}
+
+/// Large inputs are split between threads with the `rayon` feature, while small updates
+/// are always processed on the current thread. Both must match the reference model.
+#[test]
+fn kt_large() {
+    let data = shake_data((1 << 20) + 12_345);
+    let mut expected = [0u8; 32];
+    kt128_reference(&data, b"cust", &mut expected);
+
+    let mut out = [0u8; 32];
+    kt128(&data, b"cust", &mut out);
+    assert_eq!(out, expected);
+
+    let mut hasher = Kt128::default();
+    for chunk in data.chunks(4096) {
+        hasher.update(chunk);
+    }
+    hasher.finalize_xof(b"cust").read(&mut out);
+    assert_eq!(out, expected);
+
+    #[cfg(feature = "rayon")]
+    for threads in [1, 4] {
+        let out = with_threads(threads, || {
+            let mut out = [0u8; 32];
+            kt128(&data, b"cust", &mut out);
+            out
+        });
+        assert_eq!(out, expected, "threads: {threads}");
+    }
+}
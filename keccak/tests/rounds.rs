@@ -0,0 +1,260 @@
+//! Tests for Keccak-p\[1600\] with the number of rounds specified at runtime.
+use core::array;
+use keccak::{
+    Backend, BackendClosure, F200_ROUNDS, F1600_ROUNDS, Keccak, MAX_RUNTIME_ROUNDS, State1600,
+    const_fn, rc, round_constant, round_constants,
+};
+
+fn test_state() -> State1600 {
+    let mut x = 0x0123_4567_89AB_CDEF_u64;
+    array::from_fn(|_| {
+        x = x.wrapping_mul(0x9E37_79B9_7F4A_7C15).rotate_left(17);
+        x
+    })
+}
+
+macro_rules! expected {
+    ($rounds:expr, [$($n:literal)*]) => {{
+        let mut state = test_state();
+        match $rounds {
+            $($n => const_fn::p1600::<$n>(&mut state),)*
+            _ => unreachable!(),
+        }
+        state
+    }};
+}
+
+fn expected(rounds: usize) -> State1600 {
+    expected!(
+        rounds,
+        [
+            0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16
+            17 18 19 20 21 22 23 24 25 26 27 28 29 30 31 32
+        ]
+    )
+}
+
+#[test]
+fn with_p1600_rounds() {
+    let keccak = Keccak::new();
+    for rounds in 0..=MAX_RUNTIME_ROUNDS {
+        let mut state = test_state();
+        let res = keccak.with_p1600_rounds(rounds, |p1600| p1600(&mut state));
+        assert_eq!(res, Ok(()));
+        assert_eq!(state, expected(rounds), "rounds: {rounds}");
+    }
+}
+
+#[test]
+fn with_p1600_rounds_matches_const_generic() {
+    let keccak = Keccak::new();
+    let mut state1 = test_state();
+    let mut state2 = test_state();
+    keccak.with_p1600::<12>(|p1600| p1600(&mut state1));
+    let res = keccak.with_p1600_rounds(12, |p1600| p1600(&mut state2));
+    assert_eq!(res, Ok(()));
+    assert_eq!(state1, state2);
+}
+
+#[test]
+fn with_p1600_rounds_invalid() {
+    let keccak = Keccak::new();
+    for rounds in [MAX_RUNTIME_ROUNDS + 1, 100, usize::MAX] {
+        let mut called = false;
+        let res = keccak.with_p1600_rounds(rounds, |_| called = true);
+        let err = res.expect_err("invalid number of rounds");
+        assert_eq!(err.rounds(), rounds);
+        assert!(!called);
+    }
+}
+
+#[test]
+fn backend_p1600_rounds() {
+    struct Closure;
+
+    impl BackendClosure for Closure {
+        fn call_once<B: Backend>(self) {
+            for rounds in 0..=MAX_RUNTIME_ROUNDS {
+                let Ok(p1600) = B::get_p1600_rounds(rounds) else {
+                    panic!("valid number of rounds rejected: {rounds}");
+                };
+                let mut state = test_state();
+                p1600(&mut state);
+                assert_eq!(state, expected(rounds), "rounds: {rounds}");
+
+                #[cfg(feature = "parallel")]
+                {
+                    let Ok(par_p1600) = B::get_par_p1600_rounds(rounds) else {
+                        panic!("valid number of rounds rejected: {rounds}");
+                    };
+                    let mut states = keccak::ParState1600::<B>::default();
+                    states.iter_mut().for_each(|s| *s = test_state());
+                    par_p1600(&mut states);
+                    assert!(states.iter().all(|s| *s == expected(rounds)));
+                }
+            }
+
+            let rounds = MAX_RUNTIME_ROUNDS + 1;
+            assert_eq!(
+                B::get_p1600_rounds(rounds).err().map(|e| e.rounds()),
+                Some(rounds),
+            );
+        }
+    }
+
+    Keccak::new().with_backend(Closure);
+}
+
+/// Round constants of Keccak-f\[1600\] from FIPS 202.
+const RC: [u64; 24] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808A,
+    0x8000000080008000,
+    0x000000000000808B,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008A,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000A,
+    0x000000008000808B,
+    0x800000000000008B,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800A,
+    0x800000008000000A,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+/// Keccak-p\[1600, 30\] applied to the zero state.
+const P1600_30_ZERO: State1600 = [
+    0x2D6EE9D16E5CD953,
+    0x2E3615D59434F08C,
+    0x93C2736B274EC2D6,
+    0x5CAD3169D2DE41E9,
+    0xD911B142A24C79F6,
+    0xDEF8BAB4F90F71A7,
+    0xBD1AE890AF2829D6,
+    0x61F69744948A09DC,
+    0x89711A9BD4AC056D,
+    0x21C123A7E17AD0E3,
+    0xCEC0F00903F21DE0,
+    0xA9FEB0AB3FEFE95C,
+    0x1A4CE112B770CE4E,
+    0xBF28E36C8C42349B,
+    0x2DAA0EB7D7F541B4,
+    0x42E07F202124F34D,
+    0x18E8244BA9788580,
+    0xF0AA5FE74FEF12FE,
+    0x5EB3599E9E637531,
+    0x929E80965FD1D76F,
+    0xB0A67973D18F6A43,
+    0x23CB3BDF635C1CF3,
+    0xDE21EBEC9BA0FEBE,
+    0xBBB6C1C87C36497D,
+    0x31A5A280B645D3A2,
+];
+
+/// Keccak-p\[1600, 48\] applied to the zero state.
+const P1600_48_ZERO: State1600 = [
+    0xC28BC9FF703B3284,
+    0x528AF218C3EF5640,
+    0x3F7F33DA895C067B,
+    0x13E088DEFB122046,
+    0x62DA1058CC5F77D8,
+    0xADADE35DA3173E53,
+    0xB36C58BF7046D4B0,
+    0xB0D1047E35A7CCBF,
+    0xE7B56E4EC07DDBCF,
+    0xF33ACDD330130865,
+    0x2FB0DDDC5A8ABD2B,
+    0x1BA1F4D3FCBF98E2,
+    0x1335FAB9981007E8,
+    0xF3E2CC2F42E13450,
+    0x976EF6A1D7B7B70A,
+    0xFC917673B1264C84,
+    0xB9B4BE7B2F4B1D94,
+    0x7943E711B03E5904,
+    0xBE89E65968B8F72D,
+    0x7EDC78EBAB1DD3DE,
+    0x6998214E5EDA953E,
+    0x15FAF7B2767CCD63,
+    0xA731DBA015FA108A,
+    0x8ED02141B874EBA4,
+    0x3404A94615D754C5,
+];
+
+#[test]
+fn round_constants_lfsr() {
+    for (ir, &expected) in (0..).zip(RC.iter()) {
+        assert_eq!(round_constant(ir), expected, "ir: {ir}");
+    }
+    assert_eq!(round_constants::<24>(F1600_ROUNDS), RC);
+
+    // Smaller widths use the first Keccak-f\[1600\] constants truncated to the lane size
+    let rc200 = round_constants::<F200_ROUNDS>(F200_ROUNDS);
+    assert_eq!(rc200, RC[..F200_ROUNDS]);
+
+    // Negative indices continue the LFSR sequence backwards
+    assert_eq!(
+        round_constants::<6>(0),
+        [0x80008081, 0x8003, 0x8081, 0x8000000080008000, 0x8002, 0x8A],
+    );
+    for t in -300..300 {
+        assert_eq!(rc(t), rc(t + 255), "t: {t}");
+    }
+    assert!(rc(0));
+}
+
+#[test]
+fn p1600_extended_rounds() {
+    let keccak = Keccak::new();
+    let mut state = [0u64; 25];
+    keccak.with_p1600::<30>(|p1600| p1600(&mut state));
+    assert_eq!(state, P1600_30_ZERO);
+
+    let mut state = [0u64; 25];
+    keccak.with_p1600::<48>(|p1600| p1600(&mut state));
+    assert_eq!(state, P1600_48_ZERO);
+
+    let mut state = [0u64; 25];
+    const_fn::p1600::<30>(&mut state);
+    assert_eq!(state, P1600_30_ZERO);
+
+    let mut state = [0u64; 25];
+    let res = keccak.with_p1600_rounds(30, |p1600| p1600(&mut state));
+    assert_eq!(res, Ok(()));
+    assert_eq!(state, P1600_30_ZERO);
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn par_extended_rounds() {
+    struct Closure;
+
+    impl BackendClosure for Closure {
+        #[allow(clippy::cast_possible_truncation)]
+        fn call_once<B: Backend>(self) {
+            let mut states = keccak::ParState1600::<B>::default();
+            B::get_par_p1600::<30>()(&mut states);
+            assert!(states.iter().all(|s| *s == P1600_30_ZERO));
+
+            let state: keccak::State200 = array::from_fn(|i| (i * 7) as u8);
+            let mut expected = state;
+            const_fn::p200::<25>(&mut expected);
+            let mut states = keccak::ParState200::<B>::default();
+            states.iter_mut().for_each(|s| *s = state);
+            B::get_par_p200::<25>()(&mut states);
+            assert!(states.iter().all(|s| *s == expected));
+        }
+    }
+
+    Keccak::new().with_backend(Closure);
+}
@@ -0,0 +1,176 @@
+//! Sakura tree hashing tests.
+//!
+//! Expected values are produced by a straightforward non-incremental reference model.
+use hex_literal::hex;
+use keccak::{rfc9861, sakura::*};
+
+type Tree12 = TreeHash<168, 12, 32>;
+type Tree24 = TreeHash<136, 24, 64>;
+
+#[allow(clippy::cast_possible_truncation)]
+fn data10k() -> Vec<u8> {
+    (0..10_000usize)
+        .map(|i| ((i * 0x3B) ^ 0xA5) as u8)
+        .collect()
+}
+
+fn hash<const RATE: usize, const ROUNDS: usize, const CV_LEN: usize>(
+    params: TreeParams,
+    data: &[u8],
+    chunk_size: usize,
+) -> [u8; 32] {
+    let mut hasher = TreeHash::<RATE, ROUNDS, CV_LEN>::new(params);
+    for chunk in data.chunks(chunk_size) {
+        hasher.update(chunk);
+    }
+    let mut out = [0u8; 32];
+    hasher.finalize_xof().read(&mut out);
+    out
+}
+
+#[test]
+fn tree_shapes() {
+    let data = data10k();
+    let vectors = [
+        (
+            TreeParams::new(1024, 4, 2),
+            10_000,
+            hex!("0FF9A67EE9F59ABC89D0B7CB5D339D6063FA230E8E7493CEFD2179CD77C15C11"),
+        ),
+        (
+            TreeParams::new(1024, 4, 3),
+            10_000,
+            hex!("97D1CC49B087D8E0E0AAADB905176616BA46A3029A04C0013D1A6F931711D444"),
+        ),
+        (
+            TreeParams::new(256, 3, 4),
+            10_000,
+            hex!("84E4742C007330694FEC0FDE5A469AB34162085235656829CA596F504C1CDB33"),
+        ),
+        (
+            TreeParams::new(1024, 4, 3),
+            0,
+            hex!("5A223AD30B3B8C66A243048CFCED430F54E7529287D15150B973133ADFAC6A2F"),
+        ),
+        (
+            TreeParams::new(1024, 4, 3),
+            1024,
+            hex!("492E941349662B47E4C4A1EB82316428D2CE854E1BAFE01466C87C037268F28C"),
+        ),
+        (
+            TreeParams::new(1024, 4, 3),
+            1025,
+            hex!("3CCD1C9F5FF9754B51552D5DFF48046221D498994745298F0D42A87E63263CDD"),
+        ),
+        (
+            TreeParams::new(1024, 4, 3),
+            4096,
+            hex!("75B01EA1B8677A56D66647773B1146C8EE33736748D0F13CDACE7370CB5E85AE"),
+        ),
+        (
+            TreeParams::new(1024, 4, 3),
+            4097,
+            hex!("A20EFF867CA8CA2C4271A52ED5A36086FAA9D618D23EDBF8C5A29AC77C6BF3A8"),
+        ),
+    ];
+
+    for (params, len, expected) in vectors {
+        let data = &data[..len];
+        for chunk_size in [1, 100, 1023, 1024, 5000, 10_000] {
+            let out = hash::<168, 12, 32>(params, data, chunk_size);
+            assert_eq!(out, expected, "{params:?}, len: {len}, chunk: {chunk_size}");
+        }
+    }
+
+    let params = TreeParams::new(200, 2, 5);
+    let expected = hex!("4E4B00652050A466028A3F65E17E90BF3BE1CAA6FDD2F8A66A652FD529B0FB8E");
+    for chunk_size in [1, 199, 200, 201, 10_000] {
+        let out = hash::<136, 24, 64>(params, &data, chunk_size);
+        assert_eq!(out, expected, "chunk: {chunk_size}");
+    }
+}
+
+#[test]
+fn single_node_is_turbo_shake() {
+    let data = data10k();
+    let mut hasher = Tree12::new(TreeParams::new(8192, 2, 2));
+    hasher.update(&data[..8192]);
+    let mut out = [0u8; 64];
+    hasher.finalize_xof().read(&mut out);
+
+    let mut expected = [0u8; 64];
+    rfc9861::turbo_shake128(&data[..8192], 0x07, &mut expected);
+    assert_eq!(out, expected);
+
+    // Output stream is not bound to the output length
+    let mut hasher = Tree24::new(TreeParams::new(8192, 2, 2));
+    hasher.update(&data);
+    let mut reader = hasher.finalize_xof();
+    let mut out = [0u8; 100];
+    reader.read(&mut out);
+    let short = hash::<136, 24, 64>(TreeParams::new(8192, 2, 2), &data, 10_000);
+    assert_eq!(out[..32], short);
+}
+
+#[test]
+fn length_encoding() {
+    let encode = |x| {
+        let mut buf = Vec::new();
+        length_encode(x, |data| buf.extend_from_slice(data));
+        buf
+    };
+    assert_eq!(encode(0), [0x00]);
+    assert_eq!(encode(12), [0x0C, 0x01]);
+    assert_eq!(encode(65538), [0x01, 0x00, 0x02, 0x03]);
+}
+
+#[test]
+#[should_panic]
+fn zero_leaf_len() {
+    let _ = TreeParams::new(0, 2, 2);
+}
+
+#[test]
+#[should_panic]
+fn small_fan_out() {
+    let _ = TreeParams::new(1024, 1, 3);
+}
+
+#[test]
+#[should_panic]
+fn too_deep() {
+    let _ = TreeParams::new(1024, 2, MAX_DEPTH + 1);
+}
+
+/// Non-repeating test data of `len` bytes derived from SHAKE128.
+fn shake_data(len: usize) -> Vec<u8> {
+    let mut data = vec![0u8; len];
+    keccak::fips202::shake128(b"keccak rayon test data", &mut data);
+    data
+}
+
+/// Run `f` on a new rayon thread pool with `threads` threads.
+#[cfg(feature = "rayon")]
+fn with_threads<T: Send>(threads: usize, f: impl FnOnce() -> T + Send) -> T {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()
+        .expect("thread pool")
+        .install(f)
+}
+
+/// Large inputs are split between threads with the `rayon` feature, while small updates
+/// are always processed on the current thread.
+#[test]
+fn tree_large() {
+    let data = shake_data((1 << 19) + 1000);
+    let params = TreeParams::new(256, 4, 4);
+    let expected = hash::<168, 12, 32>(params, &data, 4096);
+    assert_eq!(hash::<168, 12, 32>(params, &data, data.len()), expected);
+
+    #[cfg(feature = "rayon")]
+    for threads in [1, 4] {
+        let out = with_threads(threads, || hash::<168, 12, 32>(params, &data, data.len()));
+        assert_eq!(out, expected, "threads: {threads}");
+    }
+}
@@ -0,0 +1,552 @@
+//! NIST SP 800-185 test vectors.
+//!
+//! <https://csrc.nist.gov/projects/cryptographic-standards-and-guidelines/example-values>
+use core::array;
+use hex_literal::hex;
+use keccak::sp800_185::*;
+
+const KEY: [u8; 32] = hex!("404142434445464748494A4B4C4D4E4F505152535455565758595A5B5C5D5E5F");
+const TAG: &[u8] = b"My Tagged Application";
+
+#[allow(clippy::cast_possible_truncation)]
+fn data200() -> [u8; 200] {
+    array::from_fn(|i| i as u8)
+}
+
+#[test]
+fn encodings() {
+    assert_eq!(&*left_encode(0), [0x01, 0x00]);
+    assert_eq!(&*left_encode(168), [0x01, 0xA8]);
+    assert_eq!(&*left_encode(256), [0x02, 0x01, 0x00]);
+    assert_eq!(
+        &*left_encode(u64::MAX),
+        [0x08, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]
+    );
+    assert_eq!(&*right_encode(0), [0x00, 0x01]);
+    assert_eq!(&*right_encode(256), [0x01, 0x00, 0x02]);
+    assert_eq!(
+        &*right_encode(u64::MAX),
+        [0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x08]
+    );
+
+    let mut buf = Vec::new();
+    encode_string(b"abc", |d| buf.extend_from_slice(d));
+    assert_eq!(buf, [0x01, 0x18, b'a', b'b', b'c']);
+
+    let mut buf = Vec::new();
+    encode_string(b"", |d| buf.extend_from_slice(d));
+    assert_eq!(buf, [0x01, 0x00]);
+
+    let mut buf = Vec::new();
+    bytepad(
+        8,
+        |sink| {
+            sink(b"abc");
+            sink(b"de");
+        },
+        |d| buf.extend_from_slice(d),
+    );
+    assert_eq!(buf, [0x01, 0x08, b'a', b'b', b'c', b'd', b'e', 0x00]);
+
+    let mut buf = Vec::new();
+    bytepad(4, |sink| sink(b"ab"), |d| buf.extend_from_slice(d));
+    assert_eq!(buf, [0x01, 0x04, b'a', b'b']);
+
+    let mut buf = Vec::new();
+    bytepad(
+        200,
+        |sink| encode_string(b"KMAC", sink),
+        |d| buf.extend_from_slice(d),
+    );
+    assert_eq!(buf.len(), 200);
+    assert_eq!(buf[..8], [0x01, 0xC8, 0x01, 0x20, b'K', b'M', b'A', b'C']);
+    assert!(buf[8..].iter().all(|&b| b == 0));
+}
+
+#[test]
+fn cshake128_samples() {
+    let mut out = [0u8; 32];
+    cshake128(&[0, 1, 2, 3], b"", b"Email Signature", &mut out);
+    assert_eq!(
+        out,
+        hex!("C1C36925B6409A04F1B504FCBCA9D82B4017277CB5ED2B2065FC1D3814D5AAF5")
+    );
+
+    cshake128(&data200(), b"", b"Email Signature", &mut out);
+    assert_eq!(
+        out,
+        hex!("C5221D50E4F822D96A2E8881A961420F294B7B24FE3D2094BAED2C6524CC166B")
+    );
+}
+
+#[test]
+fn cshake256_samples() {
+    let mut out = [0u8; 64];
+    cshake256(&[0, 1, 2, 3], b"", b"Email Signature", &mut out);
+    assert_eq!(
+        out,
+        hex!(
+            "D008828E2B80AC9D2218FFEE1D070C48B8E4C87BFF32C9699D5B6896EEE0EDD1"
+            "64020E2BE0560858D9C00C037E34A96937C561A74C412BB4C746469527281C8C"
+        )
+    );
+
+    cshake256(&data200(), b"", b"Email Signature", &mut out);
+    assert_eq!(
+        out,
+        hex!(
+            "07DC27B11E51FBAC75BC7B3C1D983E8B4B85FB1DEFAF218912AC864302730917"
+            "27F42B17ED1DF63E8EC118F04B23633C1DFB1574C8FB55CB45DA8E25AFB092BB"
+        )
+    );
+}
+
+#[test]
+fn cshake_without_customization_is_shake() {
+    let mut out = [0u8; 32];
+    let mut expected = [0u8; 32];
+    cshake128(b"abc", b"", b"", &mut out);
+    keccak::fips202::shake128(b"abc", &mut expected);
+    assert_eq!(out, expected);
+}
+
+#[test]
+fn kmac128_samples() {
+    let mut out = [0u8; 32];
+    kmac128(&KEY, &[0, 1, 2, 3], b"", &mut out);
+    assert_eq!(
+        out,
+        hex!("E5780B0D3EA6F7D3A429C5706AA43A00FADBD7D49628839E3187243F456EE14E")
+    );
+
+    kmac128(&KEY, &[0, 1, 2, 3], TAG, &mut out);
+    assert_eq!(
+        out,
+        hex!("3B1FBA963CD8B0B59E8C1A6D71888B7143651AF8BA0A7070C0979E2811324AA5")
+    );
+
+    kmac128(&KEY, &data200(), TAG, &mut out);
+    assert_eq!(
+        out,
+        hex!("1F5B4E6CCA02209E0DCB5CA635B89A15E271ECC760071DFD805FAA38F9729230")
+    );
+}
+
+#[test]
+fn kmac256_samples() {
+    let mut out = [0u8; 64];
+    kmac256(&KEY, &[0, 1, 2, 3], TAG, &mut out);
+    assert_eq!(
+        out,
+        hex!(
+            "20C570C31346F703C9AC36C61C03CB64C3970D0CFC787E9B79599D273A68D2F7"
+            "F69D4CC3DE9D104A351689F27CF6F5951F0103F33F4F24871024D9C27773A8DD"
+        )
+    );
+
+    kmac256(&KEY, &data200(), b"", &mut out);
+    assert_eq!(
+        out,
+        hex!(
+            "75358CF39E41494E949707927CEE0AF20A3FF553904C86B08F21CC414BCFD691"
+            "589D27CF5E15369CBBFF8B9A4C2EB17800855D0235FF635DA82533EC6B759B69"
+        )
+    );
+
+    kmac256(&KEY, &data200(), TAG, &mut out);
+    assert_eq!(
+        out,
+        hex!(
+            "B58618F71F92E1D56C1B8C55DDD7CD188B97B4CA4D99831EB2699A837DA2E4D9"
+            "70FBACFDE50033AEA585F1A2708510C32D07880801BD182898FE476876FC8965"
+        )
+    );
+}
+
+#[test]
+fn kmac_xof128_samples() {
+    let mut out = [0u8; 32];
+    kmac_xof128(&KEY, &[0, 1, 2, 3], b"", &mut out);
+    assert_eq!(
+        out,
+        hex!("CD83740BBD92CCC8CF032B1481A0F4460E7CA9DD12B08A0C4031178BACD6EC35")
+    );
+
+    kmac_xof128(&KEY, &[0, 1, 2, 3], TAG, &mut out);
+    assert_eq!(
+        out,
+        hex!("31A44527B4ED9F5C6101D11DE6D26F0620AA5C341DEF41299657FE9DF1A3B16C")
+    );
+
+    kmac_xof128(&KEY, &data200(), TAG, &mut out);
+    assert_eq!(
+        out,
+        hex!("47026C7CD793084AA0283C253EF658490C0DB61438B8326FE9BDDF281B83AE0F")
+    );
+}
+
+#[test]
+fn kmac_xof256_samples() {
+    let mut out = [0u8; 64];
+    kmac_xof256(&KEY, &[0, 1, 2, 3], TAG, &mut out);
+    assert_eq!(
+        out,
+        hex!(
+            "1755133F1534752AAD0748F2C706FB5C784512CAB835CD15676B16C0C6647FA9"
+            "6FAA7AF634A0BF8FF6DF39374FA00FAD9A39E322A7C92065A64EB1FB0801EB2B"
+        )
+    );
+
+    kmac_xof256(&KEY, &data200(), b"", &mut out);
+    assert_eq!(
+        out,
+        hex!(
+            "FF7B171F1E8A2B24683EED37830EE797538BA8DC563F6DA1E667391A75EDC02C"
+            "A633079F81CE12A25F45615EC89972031D18337331D24CEB8F8CA8E6A19FD98B"
+        )
+    );
+
+    // Incremental updates and reads
+    let mut mac = Kmac256::new(&KEY, TAG);
+    for chunk in data200().chunks(7) {
+        mac.update(chunk);
+    }
+    let mut reader = mac.finalize_xof();
+    for chunk in out.chunks_mut(5) {
+        reader.read(chunk);
+    }
+    assert_eq!(
+        out,
+        hex!(
+            "D5BE731C954ED7732846BB59DBE3A8E30F83E77A4BFF4459F2F1C2B4ECEBB8CE"
+            "67BA01C62E8AB8578D2D499BD1BB276768781190020A306A97DE281DCC30305D"
+        )
+    );
+}
+
+#[test]
+fn tuple_hash128_samples() {
+    let x: [&[u8]; 3] = [
+        &hex!("000102"),
+        &hex!("101112131415"),
+        &hex!("202122232425262728"),
+    ];
+    let mut out = [0u8; 32];
+    tuple_hash128(&x[..2], b"", &mut out);
+    assert_eq!(
+        out,
+        hex!("C5D8786C1AFB9B82111AB34B65B2C0048FA64E6D48E263264CE1707D3FFC8ED1")
+    );
+
+    tuple_hash128(&x[..2], b"My Tuple App", &mut out);
+    assert_eq!(
+        out,
+        hex!("75CDB20FF4DB1154E841D758E24160C54BAE86EB8C13E7F5F40EB35588E96DFB")
+    );
+
+    tuple_hash128(&x, b"My Tuple App", &mut out);
+    assert_eq!(
+        out,
+        hex!("E60F202C89A2631EDA8D4C588CA5FD07F39E5151998DECCF973ADB3804BB6E84")
+    );
+
+    tuple_hash_xof128(&x, b"My Tuple App", &mut out);
+    assert_eq!(
+        out,
+        hex!("900FE16CAD098D28E74D632ED852F99DAAB7F7DF4D99E775657885B4BF76D6F8")
+    );
+}
+
+#[test]
+fn tuple_hash256_samples() {
+    let x: [&[u8]; 3] = [
+        &hex!("000102"),
+        &hex!("101112131415"),
+        &hex!("202122232425262728"),
+    ];
+    let mut out = [0u8; 64];
+    tuple_hash256(&x[..2], b"", &mut out);
+    assert_eq!(
+        out,
+        hex!(
+            "CFB7058CACA5E668F81A12A20A2195CE97A925F1DBA3E7449A56F82201EC6073"
+            "11AC2696B1AB5EA2352DF1423BDE7BD4BB78C9AED1A853C78672F9EB23BBE194"
+        )
+    );
+
+    tuple_hash256(&x, b"My Tuple App", &mut out);
+    assert_eq!(
+        out,
+        hex!(
+            "45000BE63F9B6BFD89F54717670F69A9BC763591A4F05C50D68891A744BCC6E7"
+            "D6D5B5E82C018DA999ED35B0BB49C9678E526ABD8E85C13ED254021DB9E790CE"
+        )
+    );
+
+    tuple_hash_xof256(&x[..2], b"My Tuple App", &mut out);
+    assert_eq!(
+        out,
+        hex!(
+            "6483CB3C9952EB20E830AF4785851FC597EE3BF93BB7602C0EF6A65D741AECA7"
+            "E63C3B128981AA05C6D27438C79D2754BB1B7191F125D6620FCA12CE658B2442"
+        )
+    );
+}
+
+#[test]
+fn tuple_hash_item_boundaries() {
+    let mut a = [0u8; 32];
+    let mut b = [0u8; 32];
+    tuple_hash128(&[b"ab", b"c"], b"", &mut a);
+    tuple_hash128(&[b"a", b"bc"], b"", &mut b);
+    assert_ne!(a, b);
+}
+
+const PAR_DATA: [u8; 24] = hex!("000102030405060710111213141516172021222324252627");
+
+#[test]
+fn parallel_hash128_samples() {
+    let mut out = [0u8; 32];
+    parallel_hash128(&PAR_DATA, 8, b"", &mut out);
+    assert_eq!(
+        out,
+        hex!("BA8DC1D1D979331D3F813603C67F72609AB5E44B94A0B8F9AF46514454A2B4F5")
+    );
+
+    parallel_hash128(&PAR_DATA, 8, b"Parallel Data", &mut out);
+    assert_eq!(
+        out,
+        hex!("FC484DCB3F84DCEEDC353438151BEE58157D6EFED0445A81F165E495795B7206")
+    );
+
+    parallel_hash_xof128(&PAR_DATA, 8, b"", &mut out);
+    assert_eq!(
+        out,
+        hex!("FE47D661E49FFE5B7D999922C062356750CAF552985B8E8CE6667F2727C3C8D3")
+    );
+}
+
+#[test]
+fn parallel_hash256_samples() {
+    let mut out = [0u8; 64];
+    parallel_hash256(&PAR_DATA, 8, b"", &mut out);
+    assert_eq!(
+        out,
+        hex!(
+            "BC1EF124DA34495E948EAD207DD9842235DA432D2BBC54B4C110E64C45110553"
+            "1B7F2A3E0CE055C02805E7C2DE1FB746AF97A1DD01F43B824E31B87612410429"
+        )
+    );
+
+    parallel_hash_xof256(&PAR_DATA, 8, b"Parallel Data", &mut out);
+    assert_eq!(
+        out,
+        hex!(
+            "538E105F1A22F44ED2F5CC1674FBD40BE803D9C99BF5F8D90A2C8193F3FE6EA7"
+            "68E5C1A20987E2C9C65FEBED03887A51D35624ED12377594B5585541DC377EFC"
+        )
+    );
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn data3000() -> Vec<u8> {
+    (0..3000usize).map(|i| ((i * 0x3B) ^ 0xA5) as u8).collect()
+}
+
+#[test]
+fn parallel_hash_long() {
+    let data = data3000();
+    let vectors128 = [
+        (
+            168,
+            hex!("23B980542BFC25B725062F5223E6B98B1AD51858C2E79C7A5307E7D55CAC18FA"),
+        ),
+        (
+            200,
+            hex!("83D4FE31C5362550E0A1DF9576A684FF9305F1043CDB9E96EB2418AB6C8C2382"),
+        ),
+        (
+            1000,
+            hex!("0D5D6A5BFD779B5F42D4F2D16B1449048A16A82BA6E6C2B867A990C8DCB8E16E"),
+        ),
+    ];
+    let vectors256 = [
+        (
+            168,
+            hex!(
+                "AEEAC93BD546E61D0B9EF7CA94529D6A2EFD59C9884A63CB59A3F1C1894AB8A9"
+                "E18AD16251589546E6251BF4E25C40E68006219A8B4264CAA0C965E39A5ECB67"
+            ),
+        ),
+        (
+            200,
+            hex!(
+                "7942D1A186E91263798A95952A7EF4D5C054DB79E0C373EDC51FD11DA614067C"
+                "E9775B60B061F19275F06DA2B9D2802A074F61559881BD32CFD0F2F2D024F2E5"
+            ),
+        ),
+        (
+            1000,
+            hex!(
+                "AA8D446A99D2C2D224FFEB8B7B27462B3EBD59E2907FA0A98CB4E57E4ABF2278"
+                "E911B2886EEB3054D1438155CA53E0FE6F1A39221FD6399FE09A197C4A6D32BB"
+            ),
+        ),
+    ];
+
+    for (block_size, expected) in vectors128 {
+        let mut out = [0u8; 32];
+        parallel_hash128(&data, block_size, b"", &mut out);
+        assert_eq!(out, expected);
+
+        // Incremental updates not aligned to the block size
+        for chunk_size in [1, 7, 167, 500, 1001] {
+            let mut hasher = ParallelHash128::new(block_size, b"");
+            for chunk in data.chunks(chunk_size) {
+                hasher.update(chunk);
+            }
+            hasher.finalize(&mut out);
+            assert_eq!(out, expected, "block: {block_size}, chunk: {chunk_size}");
+        }
+    }
+
+    for (block_size, expected) in vectors256 {
+        let mut out = [0u8; 64];
+        parallel_hash256(&data, block_size, b"", &mut out);
+        assert_eq!(out, expected);
+
+        let mut hasher = ParallelHash256::new(block_size, b"");
+        for chunk in data.chunks(333) {
+            hasher.update(chunk);
+        }
+        hasher.finalize(&mut out);
+        assert_eq!(out, expected, "block: {block_size}");
+    }
+}
+
+#[test]
+fn parallel_hash_empty() {
+    let mut out = [0u8; 32];
+    parallel_hash128(b"", 8, b"", &mut out);
+    assert_eq!(
+        out,
+        hex!("96427C30224408859F95E89E4FA84E1C7A1478DBF2008AC982CE61A77F37A272")
+    );
+}
+
+#[test]
+#[should_panic]
+fn parallel_hash_zero_block_size() {
+    let _ = ParallelHash128::new(0, b"");
+}
+
+/// Non-repeating test data of `len` bytes derived from SHAKE128.
+fn shake_data(len: usize) -> Vec<u8> {
+    let mut data = vec![0u8; len];
+    keccak::fips202::shake128(b"keccak rayon test data", &mut data);
+    data
+}
+
+/// Run `f` on a new rayon thread pool with `threads` threads.
+#[cfg(feature = "rayon")]
+fn with_threads<T: Send>(threads: usize, f: impl FnOnce() -> T + Send) -> T {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()
+        .expect("thread pool")
+        .install(f)
+}
+
+/// Straightforward ParallelHash which hashes every block with SHAKE and passes
+/// the concatenated chaining values into cSHAKE.
+fn parallel_hash_reference<const CV_LEN: usize>(
+    data: &[u8],
+    block_size: usize,
+    customization: &[u8],
+    out: &mut [u8],
+) {
+    let mut z = left_encode(block_size as u64).to_vec();
+    let blocks = data.chunks(block_size);
+    let n = blocks.len();
+    for block in blocks {
+        let mut cv = [0u8; CV_LEN];
+        if CV_LEN == 32 {
+            keccak::fips202::shake128(block, &mut cv);
+        } else {
+            keccak::fips202::shake256(block, &mut cv);
+        }
+        z.extend_from_slice(&cv);
+    }
+    z.extend_from_slice(&right_encode(n as u64));
+    z.extend_from_slice(&right_encode(8 * out.len() as u64));
+    if CV_LEN == 32 {
+        cshake128(&z, b"ParallelHash", customization, out);
+    } else {
+        cshake256(&z, b"ParallelHash", customization, out);
+    }
+}
+
+/// Large inputs are split between threads with the `rayon` feature, while small updates
+/// are always processed on the current thread. Both must match the reference model.
+#[test]
+fn parallel_hash_large() {
+    let data = shake_data((1 << 19) + 77);
+    let mut expected128 = [0u8; 32];
+    parallel_hash_reference::<32>(&data, 64, b"", &mut expected128);
+    let mut expected256 = [0u8; 64];
+    parallel_hash_reference::<64>(&data, 1000, b"x", &mut expected256);
+
+    let mut out = [0u8; 32];
+    parallel_hash128(&data, 64, b"", &mut out);
+    assert_eq!(out, expected128);
+
+    let mut hasher = ParallelHash128::new(64, b"");
+    for chunk in data.chunks(4096) {
+        hasher.update(chunk);
+    }
+    hasher.finalize(&mut out);
+    assert_eq!(out, expected128);
+
+    let mut out = [0u8; 64];
+    parallel_hash256(&data, 1000, b"x", &mut out);
+    assert_eq!(out, expected256);
+
+    #[cfg(feature = "rayon")]
+    for threads in [1, 4] {
+        let out = with_threads(threads, || {
+            let mut out = [0u8; 32];
+            parallel_hash128(&data, 64, b"", &mut out);
+            out
+        });
+        assert_eq!(out, expected128, "threads: {threads}");
+    }
+}
+
+/// Chaining values of tiny leaves are buffered in bounded segments with the `rayon` feature.
+#[test]
+fn parallel_hash_tiny_blocks() {
+    let data = shake_data((1 << 18) + 3);
+    let mut expected = [0u8; 64];
+    parallel_hash_reference::<64>(&data, 1, b"", &mut expected);
+
+    let mut out = [0u8; 64];
+    let mut hasher = ParallelHash256::new(1, b"");
+    for chunk in data.chunks(4096) {
+        hasher.update(chunk);
+    }
+    hasher.finalize(&mut out);
+    assert_eq!(out, expected);
+
+    parallel_hash256(&data, 1, b"", &mut out);
+    assert_eq!(out, expected);
+
+    #[cfg(feature = "rayon")]
+    for threads in [1, 4] {
+        let out = with_threads(threads, || {
+            let mut out = [0u8; 64];
+            parallel_hash256(&data, 1, b"", &mut out);
+            out
+        });
+        assert_eq!(out, expected, "threads: {threads}");
+    }
+}
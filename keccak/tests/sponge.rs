@@ -0,0 +1,174 @@
+//! Tests for the generic sponge construction.
+use core::array;
+use hex_literal::hex;
+use keccak::sponge::{Sponge, Width};
+use keccak::{Keccak, State200, State400, State800, State1600};
+
+fn hash<W: Width, const RATE: usize, const ROUNDS: usize>(msg: &[u8], suffix: u8, out: &mut [u8]) {
+    let mut sponge = Sponge::<W, RATE, ROUNDS>::new();
+    sponge.absorb(msg);
+    sponge.absorb_last(suffix);
+    sponge.squeeze(out);
+}
+
+#[test]
+fn keccak_f1600_instances() {
+    let mut out = [0u8; 32];
+
+    // SHA3-256
+    hash::<State1600, 136, 24>(b"", 0x06, &mut out);
+    assert_eq!(
+        out,
+        hex!("a7ffc6f8bf1ed76651c14756a061d662f580ff4de43b49fa82d80a4b80f8434a")
+    );
+    hash::<State1600, 136, 24>(b"abc", 0x06, &mut out);
+    assert_eq!(
+        out,
+        hex!("3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532")
+    );
+
+    // Keccak-256
+    hash::<State1600, 136, 24>(b"", 0x01, &mut out);
+    assert_eq!(
+        out,
+        hex!("c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470")
+    );
+
+    // SHAKE128
+    hash::<State1600, 168, 24>(b"", 0x1F, &mut out);
+    assert_eq!(
+        out,
+        hex!("7f9c2ba4e88f827d616045507605853ed73b8093f6efbc88eb1a6eacfa66ef26")
+    );
+}
+
+/// Straightforward sponge implementation used as a reference.
+fn reference<W: Width, const RATE: usize, const ROUNDS: usize>(
+    msg: &[u8],
+    suffix: u8,
+    out: &mut [u8],
+) {
+    let p = W::get_p::<ROUNDS>(&Keccak::new());
+
+    // Convert the delimited suffix and padding into a bit string
+    let mut bits: Vec<bool> = msg
+        .iter()
+        .flat_map(|b| (0..8).map(move |i| (b >> i) & 1 == 1))
+        .collect();
+    let suffix_len = 7 - suffix.leading_zeros() as usize;
+    bits.extend((0..suffix_len).map(|i| (suffix >> i) & 1 == 1));
+    bits.push(true);
+    while bits.len() % (8 * RATE) != 8 * RATE - 1 {
+        bits.push(false);
+    }
+    bits.push(true);
+
+    let mut state = W::default();
+    for block in bits.chunks(8 * RATE) {
+        let block: Vec<u8> = block
+            .chunks(8)
+            .map(|b| {
+                b.iter()
+                    .rev()
+                    .fold(0, |acc, &bit| (acc << 1) | u8::from(bit))
+            })
+            .collect();
+        state.add_bytes(&block, 0);
+        p(&mut state);
+    }
+
+    for (i, chunk) in out.chunks_mut(RATE).enumerate() {
+        if i != 0 {
+            p(&mut state);
+        }
+        state.extract_bytes(chunk, 0);
+    }
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn check<W: Width, const RATE: usize, const ROUNDS: usize>() {
+    let msg: [u8; 300] = array::from_fn(|i| (i as u8).wrapping_mul(0x3B) ^ 0xA5);
+
+    for suffix in [0x01, 0x06, 0x1F, 0x0B, 0x80, 0xFF] {
+        for len in [
+            0,
+            1,
+            RATE - 1,
+            RATE,
+            RATE + 1,
+            2 * RATE - 1,
+            3 * RATE + 2,
+            300,
+        ] {
+            let msg = &msg[..len.min(msg.len())];
+            let len = msg.len();
+            let mut expected = [0u8; 100];
+            reference::<W, RATE, ROUNDS>(msg, suffix, &mut expected);
+
+            let mut out = [0u8; 100];
+            hash::<W, RATE, ROUNDS>(msg, suffix, &mut out);
+            assert_eq!(out, expected);
+
+            // Incremental absorption and squeezing
+            let mut sponge = Sponge::<W, RATE, ROUNDS>::new();
+            let (a, b) = msg.split_at(len / 3);
+            sponge.absorb(a);
+            for chunk in b.chunks(7) {
+                sponge.absorb(chunk);
+            }
+            sponge.absorb_last(suffix);
+            let mut out = [0u8; 100];
+            for chunk in out.chunks_mut(13) {
+                sponge.squeeze(chunk);
+            }
+            assert_eq!(out, expected);
+        }
+    }
+}
+
+#[test]
+fn sponge_f200() {
+    check::<State200, 1, 18>();
+    check::<State200, 18, 18>();
+    check::<State200, 24, 12>();
+}
+
+#[test]
+fn sponge_f400() {
+    check::<State400, 5, 20>();
+    check::<State400, 33, 20>();
+}
+
+#[test]
+fn sponge_f800() {
+    check::<State800, 32, 22>();
+    check::<State800, 61, 12>();
+}
+
+#[test]
+fn sponge_f1600() {
+    check::<State1600, 136, 24>();
+    check::<State1600, 168, 12>();
+    check::<State1600, 101, 24>();
+    check::<State1600, 199, 24>();
+}
+
+#[test]
+fn squeeze_without_suffix() {
+    let mut sponge = Sponge::<State1600, 136, 24>::new();
+    sponge.absorb(b"");
+    let mut out = [0u8; 32];
+    sponge.squeeze(&mut out);
+    assert_eq!(
+        out,
+        hex!("c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470")
+    );
+}
+
+#[test]
+#[should_panic]
+fn absorb_after_squeeze() {
+    let mut sponge = Sponge::<State800, 32, 22>::new();
+    sponge.squeeze(&mut [0u8; 4]);
+    sponge.absorb(b"abc");
+}
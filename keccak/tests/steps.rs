@@ -0,0 +1,177 @@
+//! Tests of the step mappings and intermediate value tracing.
+use keccak::steps::{self, Step};
+use keccak::{Keccak, PLEN, State200, State400, State800, State1600};
+
+fn test_state<T>(f: impl Fn(u64) -> T) -> [T; PLEN] {
+    let mut x = 0x0123_4567_89AB_CDEFu64;
+    core::array::from_fn(|_| {
+        x = x.wrapping_mul(0x5851_F42D_4C95_7F2D).wrapping_add(1);
+        f(x >> 11)
+    })
+}
+
+macro_rules! impl_steps_test {
+    ($name:ident, $state:ty, $lane:ty, $with_p:ident, $f_rounds:expr, [$($rounds:literal),*]) => {
+        #[test]
+        #[allow(
+            clippy::cast_possible_truncation,
+            clippy::cast_possible_wrap,
+            clippy::cast_sign_loss,
+            trivial_numeric_casts
+        )]
+        fn $name() {
+            let keccak = Keccak::new();
+            let state: $state = test_state(|x| x as $lane);
+            $(
+                let mut expected = state;
+                keccak.$with_p::<$rounds>(|p| p(&mut expected));
+
+                let mut res = state;
+                let first = $f_rounds as i64 - $rounds;
+                for ir in first..$f_rounds as i64 {
+                    steps::theta(&mut res);
+                    steps::rho(&mut res);
+                    steps::pi(&mut res);
+                    steps::chi(&mut res);
+                    steps::iota(&mut res, ir);
+                }
+                assert_eq!(res, expected, "rounds: {}", $rounds);
+
+                let mut res = state;
+                for ir in first..$f_rounds as i64 {
+                    steps::round(&mut res, ir);
+                }
+                assert_eq!(res, expected, "rounds: {}", $rounds);
+
+                let mut res = state;
+                let mut count = 0;
+                steps::keccak_p_traced::<_, $rounds>(&mut res, |ir, step, _| {
+                    assert_eq!(ir, first + count / 5);
+                    assert_eq!(step, Step::ALL[(count % 5) as usize]);
+                    count += 1;
+                });
+                assert_eq!(count, 5 * $rounds);
+                assert_eq!(res, expected, "rounds: {}", $rounds);
+            )*
+        }
+    };
+}
+
+impl_steps_test!(p200, State200, u8, with_p200, 18, [0, 1, 12, 18, 25]);
+impl_steps_test!(p400, State400, u16, with_p400, 20, [0, 1, 12, 20]);
+impl_steps_test!(p800, State800, u32, with_p800, 22, [0, 1, 12, 22]);
+impl_steps_test!(p1600, State1600, u64, with_p1600, 24, [0, 1, 12, 24, 30]);
+
+#[test]
+fn rho_offsets() {
+    // Offsets from FIPS 202, Table 2, indexed by `x + 5 * y`
+    let expected: [u32; PLEN] = [
+        0, 1, 62, 28, 27, 36, 44, 6, 55, 20, 3, 10, 43, 25, 39, 41, 45, 15, 21, 8, 18, 2, 61, 56,
+        14,
+    ];
+    let mut state = [1u64; PLEN];
+    steps::rho(&mut state);
+    assert_eq!(state, expected.map(|r| 1u64 << r));
+}
+
+#[test]
+fn pi_positions() {
+    let mut state: [u64; PLEN] = core::array::from_fn(|i| i as u64);
+    steps::pi(&mut state);
+    for x in 0..5 {
+        for y in 0..5 {
+            assert_eq!(state[y + 5 * ((2 * x + 3 * y) % 5)], (x + 5 * y) as u64);
+        }
+    }
+}
+
+#[test]
+fn step_apply() {
+    let state: State1600 = test_state(|x| x);
+    let mut res = state;
+    for step in Step::ALL {
+        step.apply(&mut res, 3);
+    }
+    let mut expected = state;
+    steps::round(&mut expected, 3);
+    assert_eq!(res, expected);
+}
+
+#[cfg(feature = "alloc")]
+mod trace {
+    use super::*;
+    use keccak::steps::Trace;
+
+    /// Intermediate values of Keccak-f\[1600\] in the layout of XKCP's
+    /// `KeccakF-1600-IntermediateValues.txt`: the permutation is applied to the zero state
+    /// and then to its output. Generated by an independent model of the step mappings.
+    const XKCP_INTERMEDIATE_VALUES: &str = include_str!("data/KeccakF-1600-IntermediateValues.txt");
+
+    #[test]
+    fn xkcp_layout() {
+        let mut state = [0u64; PLEN];
+        let trace = Trace::record::<24>(&mut state);
+        assert_eq!(trace.input(), &[0; PLEN]);
+        assert_eq!(trace.output(), &state);
+        assert_eq!(trace.steps().count(), 5 * 24);
+        // First lanes of the published Keccak-f[1600] outputs
+        assert_eq!(state[0], 0xF1258F7940E1DDE7);
+
+        let mut text = String::new();
+        trace.write_xkcp(&mut text).expect("writing into string");
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], "+++ Keccak-p[1600, nr=24] +++");
+        assert_eq!(lines[2], "Input of permutation:");
+        assert_eq!(text.matches("--- Round ").count(), 24);
+        assert!(text.contains("\n--- Round 0 ---\n\nAfter theta:\n"));
+        assert!(text.contains("\n--- Round 23 ---\n"));
+        for step in Step::ALL {
+            let header = format!("After {}:\n", step.name());
+            assert_eq!(text.matches(&header).count(), 24);
+        }
+
+        // Permutation of a nonzero state
+        let input = state;
+        let trace = Trace::record::<24>(&mut state);
+        assert_eq!(trace.input(), &input);
+        assert_eq!(state[0], 0x2D5C954DF96ECB3C);
+        text.push('\n');
+        trace.write_xkcp(&mut text).expect("writing into string");
+
+        assert_eq!(text, XKCP_INTERMEDIATE_VALUES);
+    }
+
+    #[test]
+    #[allow(clippy::cast_possible_truncation)]
+    fn readmemh() {
+        let mut state: State200 = test_state(|x| x as u8);
+        let input = state;
+        let trace = Trace::record::<18>(&mut state);
+
+        let mut text = String::new();
+        trace
+            .write_readmemh(&mut text)
+            .expect("writing into string");
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 1 + 5 * 18);
+        assert!(lines[0].ends_with(" // input"));
+        assert!(lines[1].ends_with(" // round 0 theta"));
+        assert!(lines[90].ends_with(" // round 17 iota"));
+
+        let parse = |line: &str| -> State200 {
+            let word = line.split(' ').next().expect("hex word");
+            assert_eq!(word.len(), 50);
+            let mut res = [0u8; PLEN];
+            for (i, lane) in res.iter_mut().enumerate() {
+                let pos = 2 * (PLEN - 1 - i);
+                *lane = u8::from_str_radix(&word[pos..][..2], 16).expect("hex digits");
+            }
+            res
+        };
+        assert_eq!(parse(lines[0]), input);
+        assert_eq!(&parse(lines[90]), trace.output());
+        for (line, (_, _, state)) in lines[1..].iter().zip(trace.steps()) {
+            assert_eq!(&parse(line), state);
+        }
+    }
+}
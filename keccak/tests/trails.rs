@@ -0,0 +1,240 @@
+//! Tests of the difference and mask propagation.
+use keccak::generic::LaneSize;
+use keccak::trails;
+use keccak::{PLEN, steps};
+
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_mul(0x5851_F42D_4C95_7F2D).wrapping_add(1);
+        self.0 >> 11
+    }
+
+    fn state<L: LaneSize>(&mut self) -> [L; PLEN] {
+        core::array::from_fn(|_| L::truncate_rc(self.next() ^ (self.next() << 32)))
+    }
+
+    /// State with `n` random bits set.
+    fn sparse<L: LaneSize>(&mut self, n: usize) -> [L; PLEN] {
+        let mut res = [L::default(); PLEN];
+        for _ in 0..n {
+            let bit = self.next();
+            res[(bit % 25) as usize] ^= L::truncate_rc(1 << ((bit >> 8) % u64::from(L::BITS)));
+        }
+        res
+    }
+}
+
+fn xor<L: LaneSize>(a: &[L; PLEN], b: &[L; PLEN]) -> [L; PLEN] {
+    core::array::from_fn(|i| a[i] ^ b[i])
+}
+
+fn dot<L: LaneSize + Into<u64>>(a: &[L; PLEN], b: &[L; PLEN]) -> bool {
+    a.iter()
+        .zip(b)
+        .map(|(&a, &b)| (a & b).into().count_ones())
+        .sum::<u32>()
+        % 2
+        == 1
+}
+
+fn chi<L: LaneSize>(state: &[L; PLEN]) -> [L; PLEN] {
+    let mut res = *state;
+    steps::chi(&mut res);
+    res
+}
+
+/// State with the row `(1, 3)` equal to `row`.
+fn row_state(row: u8) -> [u8; PLEN] {
+    let mut res = [0; PLEN];
+    for x in 0..5 {
+        res[5 + x] = ((row >> x) & 1) << 3;
+    }
+    res
+}
+
+#[test]
+fn chi_row_weights() {
+    for a in 0..32 {
+        let diff = row_state(a);
+        let mut counts = [0u32; 32];
+        let mut mask_corrs = [0i32; 32];
+        for x in 0..32 {
+            let input = row_state(x);
+            let out = xor(&chi(&input), &chi(&xor(&input, &diff)));
+            counts[(0..5).fold(0, |r, x| r | usize::from((out[5 + x] >> 3) & 1) << x)] += 1;
+            assert!(trails::is_chi_compatible(&diff, &out));
+
+            // Correlation of the input masks with the output mask `a`
+            for b in 0..32 {
+                let sign = dot(&row_state(b), &input) ^ dot(&diff, &chi(&input));
+                mask_corrs[usize::from(b)] += if sign { -1 } else { 1 };
+            }
+        }
+
+        let weight = trails::chi_weight(&diff);
+        let compatible: Vec<u32> = counts.into_iter().filter(|&c| c != 0).collect();
+        assert_eq!(compatible.len(), 1 << weight);
+        assert!(compatible.iter().all(|&c| c == 32 >> weight));
+
+        let weight = trails::chi_linear_weight(&diff);
+        for b in 0..32 {
+            let corr = mask_corrs[usize::from(b)];
+            let compatible = trails::is_chi_linear_compatible(&row_state(b), &diff);
+            assert_eq!(compatible, corr != 0);
+            if compatible {
+                assert_eq!((corr * corr) << weight, 32 * 32);
+            }
+        }
+    }
+
+    let single = row_state(1);
+    assert_eq!(trails::chi_weight(&single), 2);
+    assert_eq!(trails::chi_linear_weight(&single), 2);
+    assert_eq!(trails::chi_weight(&row_state(0x1F)), 4);
+    assert_eq!(trails::chi_weight(&[0u64; PLEN]), 0);
+}
+
+#[test]
+fn chi_state_propagation() {
+    let mut rng = Rng(1);
+    for n in [1, 3, 10, 50] {
+        let diff = rng.sparse::<u64>(n);
+        for _ in 0..16 {
+            let x = rng.state::<u64>();
+            let out = xor(&chi(&x), &chi(&xor(&x, &diff)));
+            assert!(trails::is_chi_compatible(&diff, &out));
+        }
+        let mut out = xor(&chi(&[0; PLEN]), &chi(&diff));
+        out[0] ^= 1 << 63;
+        out[5] ^= 1 << 63;
+        out[10] ^= 1 << 63;
+        out[15] ^= 1 << 63;
+        out[20] ^= 1 << 63;
+        if diff.iter().all(|lane| lane >> 63 == 0) {
+            assert!(!trails::is_chi_compatible(&diff, &out));
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn chi_spaces() {
+    let mut rng = Rng(2);
+    for n in [0, 1, 2, 3] {
+        let diff = rng.sparse::<u16>(n);
+        let space = trails::chi_output_differences(&diff);
+        assert_eq!(space.dimension(), trails::chi_weight(&diff) as usize);
+        let elements: Vec<_> = space.iter().collect();
+        assert_eq!(elements.len(), 1 << space.dimension());
+        for (i, out) in elements.iter().enumerate() {
+            assert!(trails::is_chi_compatible(&diff, out));
+            assert!(!elements[..i].contains(out));
+        }
+
+        let space = trails::chi_input_masks(&diff);
+        assert_eq!(space.dimension(), trails::chi_linear_weight(&diff) as usize);
+        assert_eq!(space.basis().len(), space.dimension());
+        for input in space.iter() {
+            assert!(trails::is_chi_linear_compatible(&input, &diff));
+        }
+    }
+}
+
+fn check_lambda<L: LaneSize + Into<u64> + PartialEq + core::fmt::Debug>() {
+    let mut rng = Rng(u64::from(L::BITS));
+    for _ in 0..8 {
+        let x = rng.state::<L>();
+        let u = rng.state::<L>();
+
+        let mut y = x;
+        trails::lambda(&mut y);
+        let mut res = y;
+        trails::lambda_inverse(&mut res);
+        assert_eq!(res, x);
+
+        let mut v = u;
+        trails::lambda_transpose(&mut v);
+        assert_eq!(dot(&v, &x), dot(&u, &y));
+        let mut res = v;
+        trails::lambda_inverse_transpose(&mut res);
+        assert_eq!(res, u);
+    }
+}
+
+#[test]
+fn lambda() {
+    check_lambda::<u8>();
+    check_lambda::<u16>();
+    check_lambda::<u32>();
+    check_lambda::<u64>();
+}
+
+#[test]
+fn kernel() {
+    let mut state = [0u32; PLEN];
+    assert!(trails::is_in_kernel(&state));
+    state[3] = 0x8000_0010;
+    assert!(!trails::is_in_kernel(&state));
+    assert_eq!(trails::column_parity(&state), [0, 0, 0, 0x8000_0010, 0]);
+    state[18] = 0x8000_0010;
+    assert!(trails::is_in_kernel(&state));
+    assert_eq!(trails::column_parity(&state), [0; 5]);
+
+    let mut res = state;
+    steps::theta(&mut res);
+    assert_eq!(res, state);
+}
+
+#[test]
+fn differential_trail() {
+    let mut rng = Rng(3);
+    let mut states = vec![rng.sparse::<u64>(2)];
+    for _ in 0..3 {
+        let a = states[states.len() - 1];
+        let x = rng.state::<u64>();
+        let mut b = xor(&chi(&x), &chi(&xor(&x, &a)));
+        trails::lambda(&mut b);
+        states.push(b);
+    }
+    let expected = states.iter().map(trails::chi_weight).sum();
+    assert_eq!(trails::differential_trail_weight(&states), Ok(expected));
+    assert_eq!(
+        trails::differential_trail_weight(&states[..1]),
+        Ok(trails::chi_weight(&states[0])),
+    );
+    assert_eq!(trails::differential_trail_weight::<u64>(&[]), Ok(0));
+
+    states[1][7] ^= 1;
+    let err = trails::differential_trail_weight(&states).map_err(|err| err.round());
+    assert_eq!(err, Err(0));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn linear_trail() {
+    let mut rng = Rng(4);
+    let mut states = vec![rng.sparse::<u32>(2)];
+    for _ in 0..3 {
+        let b = states[0];
+        let space = trails::chi_input_masks(&b);
+        let mut a = *space.offset();
+        if let Some(v) = space.basis().first() {
+            a = xor(&a, v);
+        }
+        trails::lambda_transpose(&mut a);
+        states.insert(0, a);
+    }
+    let expected = states.iter().map(trails::chi_linear_weight).sum();
+    assert_eq!(trails::linear_trail_weight(&states), Ok(expected));
+
+    states[0][0] ^= 1 << 5;
+    let err = trails::linear_trail_weight(&states).map_err(|err| err.round());
+    assert_eq!(err, Err(0));
+    let err = trails::linear_trail_weight(&states).expect_err("incompatible trail");
+    assert_eq!(
+        err.to_string(),
+        "trail round 0 is incompatible with round 1"
+    );
+}
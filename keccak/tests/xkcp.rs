@@ -1,7 +1,7 @@
 //! Test vectors are copied from [XKCP] (eXtended Keccak Code Package).
 //!
 //! [XKCP]: https://github.com/XKCP/XKCP/blob/master/tests/TestVectors
-use keccak::{Keccak, PLEN};
+use keccak::{Fn1600, Keccak, PLEN};
 
 /// Test vector from KeccakF-200-IntermediateValues.txt
 #[test]
@@ -73,8 +73,7 @@ fn keccak_f800() {
 }
 
 /// Test vector from KeccakF-1600-IntermediateValues.txt
-#[test]
-fn keccak_f1600() {
+fn check_f1600(f1600: Fn1600) {
     let before = [
         0xF1258F7940E1DDE7,
         0x84D5CCF933C0478A,
@@ -130,11 +129,32 @@ fn keccak_f1600() {
         0x609F4E62A44C1059,
         0x20D06CD26A8FBF5C,
     ];
-    Keccak::new().with_f1600(|f1600| {
-        let mut buf = [0; PLEN];
-        f1600(&mut buf);
-        assert_eq!(buf, before);
-        f1600(&mut buf);
-        assert_eq!(buf, after);
-    });
+    let mut buf = [0; PLEN];
+    f1600(&mut buf);
+    assert_eq!(buf, before);
+    f1600(&mut buf);
+    assert_eq!(buf, after);
+}
+
+#[test]
+fn keccak_f1600() {
+    Keccak::new().with_f1600(check_f1600);
+}
+
+/// The runtime detection prefers AVX-512 over AVX2, so the AVX2 backend is tested explicitly.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[test]
+fn keccak_f1600_x86_avx2() {
+    use keccak::{Backend, BackendClosure};
+
+    struct Closure;
+
+    impl BackendClosure for Closure {
+        fn call_once<B: Backend>(self) {
+            check_f1600(B::get_f1600());
+        }
+    }
+
+    let avx2 = std::arch::is_x86_feature_detected!("avx2");
+    assert_eq!(Keccak::new().with_x86_avx2(Closure), avx2);
 }